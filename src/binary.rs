@@ -0,0 +1,31 @@
+use crate::models::CalendarEvent;
+use anyhow::{Context, Result};
+
+/// Serialize events into a compact binary form for caching between runs
+pub fn to_bytes(events: &[CalendarEvent]) -> Result<Vec<u8>> {
+    postcard::to_allocvec(events).context("Failed to serialize events")
+}
+
+/// Deserialize events previously written by [`to_bytes`]
+pub fn from_bytes(bytes: &[u8]) -> Result<Vec<CalendarEvent>> {
+    postcard::from_bytes(bytes).context("Failed to deserialize events")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{all_day, date, event, timed};
+
+    #[test]
+    fn test_round_trip() {
+        let day = date(2025, 12, 10);
+        let mut chapel = event("1", timed(day, 8, 30), timed(day, 9, 30));
+        chapel.description = Some("Whole school".to_string());
+        chapel.location = "Chapel".to_string();
+        chapel.categories = vec!["Whole School".to_string(), "Religious".to_string()];
+        let events = vec![chapel, all_day("2", date(2025, 12, 11))];
+
+        let bytes = to_bytes(&events).unwrap();
+        assert_eq!(from_bytes(&bytes).unwrap(), events);
+    }
+}
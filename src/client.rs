@@ -1,5 +1,396 @@
+use crate::fetch::{FetchOptions, dedup_and_sort, fetch_events_recursive_with_fetcher};
+use crate::models::CalendarEvent;
 use anyhow::{Context, Result};
 use chrono::NaiveDate;
+use reqwest::StatusCode;
+use reqwest::header::{ETAG, HeaderMap, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Returned when a response body is bigger than the configured limit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponseTooLarge {
+    pub limit: usize,
+}
+
+impl fmt::Display for ResponseTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Response body exceeded the {} byte limit", self.limit)
+    }
+}
+
+impl std::error::Error for ResponseTooLarge {}
+
+/// Which events SOCS should include, by audience
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Visibility {
+    /// Only published events meant for a public audience
+    PublicOnly,
+    /// Internal (staff-facing) events as well, but only published ones
+    Staff,
+    /// Everything, including internal and unpublished events
+    #[default]
+    Everything,
+}
+
+impl Visibility {
+    /// The `(IncludeInternal, IncludeUnpublished)` flags for this preset
+    fn flags(self) -> (bool, bool) {
+        match self {
+            Visibility::PublicOnly => (false, false),
+            Visibility::Staff => (true, false),
+            Visibility::Everything => (true, true),
+        }
+    }
+}
+
+/// How boolean query parameters are written
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BoolEncoding {
+    /// `1` and `0`, which most SOCS deployments expect
+    #[default]
+    Numeric,
+    /// `true` and `false`, for deployments that ignore numeric flags
+    Word,
+}
+
+impl BoolEncoding {
+    fn encode(self, value: bool) -> String {
+        match (self, value) {
+            (BoolEncoding::Numeric, true) => "1",
+            (BoolEncoding::Numeric, false) => "0",
+            (BoolEncoding::Word, true) => "true",
+            (BoolEncoding::Word, false) => "false",
+        }
+        .to_string()
+    }
+}
+
+/// Query parameters sent alongside the date range on each calendar request
+///
+/// Each field set to `None` is left off the URL entirely, for endpoints that
+/// reject parameters they don't know. The default sends `Sport=0`,
+/// `CoCurricular=0` and the flags of [`Visibility::Everything`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalendarQuery {
+    /// Include sports fixtures (`Sport`)
+    pub sport: Option<bool>,
+    /// Include co-curricular activities (`CoCurricular`)
+    pub co_curricular: Option<bool>,
+    /// Include internal (staff-facing) events (`IncludeInternal`)
+    pub include_internal: Option<bool>,
+    /// Include unpublished events (`IncludeUnpublished`)
+    pub include_unpublished: Option<bool>,
+    /// How the flags above are written
+    pub bool_encoding: BoolEncoding,
+    /// Further parameters this crate doesn't model, such as `YearGroup`,
+    /// appended in order after the ones above; like them, each replaces a
+    /// parameter of the same name on the base URL. Naming a parameter set
+    /// by the fields above (ignoring case) is an error; set that field to
+    /// `None` to send your own value
+    pub extra: Vec<(String, String)>,
+}
+
+impl Default for CalendarQuery {
+    fn default() -> Self {
+        Self {
+            sport: Some(false),
+            co_curricular: Some(false),
+            include_internal: Some(true),
+            include_unpublished: Some(true),
+            bool_encoding: BoolEncoding::Numeric,
+            extra: Vec::new(),
+        }
+    }
+}
+
+impl CalendarQuery {
+    /// Set `IncludeInternal` and `IncludeUnpublished` from a preset
+    pub fn with_visibility(mut self, visibility: Visibility) -> Self {
+        let (include_internal, include_unpublished) = visibility.flags();
+        self.include_internal = Some(include_internal);
+        self.include_unpublished = Some(include_unpublished);
+        self
+    }
+}
+
+/// A source of raw calendar XML for a date range
+///
+/// The recursive fetchers are generic over this so the HTTP layer can be
+/// swapped out, for example for canned pages in tests.
+pub trait CalendarFetcher {
+    fn fetch(
+        &self,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> impl Future<Output = Result<String>> + Send;
+}
+
+/// Fetches calendar XML from the SOCS API over HTTP
+#[derive(Debug, Clone)]
+pub struct HttpFetcher {
+    client: reqwest::Client,
+    base_url: String,
+    query: CalendarQuery,
+    max_response_bytes: Option<usize>,
+    cache: Option<Arc<ResponseCache>>,
+}
+
+impl HttpFetcher {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::with_client(reqwest::Client::new(), base_url)
+    }
+
+    /// Use an already configured HTTP client (timeouts, proxies, etc.)
+    pub fn with_client(client: reqwest::Client, base_url: impl Into<String>) -> Self {
+        Self {
+            client,
+            base_url: base_url.into(),
+            query: CalendarQuery::default(),
+            max_response_bytes: None,
+            cache: None,
+        }
+    }
+
+    /// Send `query` on every request instead of the default parameters
+    pub fn with_query(mut self, query: CalendarQuery) -> Self {
+        self.query = query;
+        self
+    }
+
+    /// Fail with [`ResponseTooLarge`] rather than read a body bigger than `limit` bytes
+    pub fn with_max_response_bytes(mut self, limit: Option<usize>) -> Self {
+        self.max_response_bytes = limit;
+        self
+    }
+
+    /// Make conditional requests against `cache`, reusing its bodies on a 304
+    pub fn with_cache(mut self, cache: Arc<ResponseCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+}
+
+impl CalendarFetcher for HttpFetcher {
+    async fn fetch(&self, start_date: NaiveDate, end_date: NaiveDate) -> Result<String> {
+        let url = build_calendar_url_with_query(&self.base_url, start_date, end_date, &self.query)?;
+        match &self.cache {
+            Some(cache) => {
+                fetch_url_cached(&self.client, &url, self.max_response_bytes, cache).await
+            }
+            None => {
+                let (body, _headers) =
+                    fetch_url(&self.client, &url, self.max_response_bytes, None).await?;
+                Ok(body)
+            }
+        }
+    }
+}
+
+/// A calendar response kept so the next request for its range can be conditional
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub body: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+/// Calendar responses keyed by request URL, for conditional GETs
+///
+/// Used by [`fetch_calendar_cached`] and [`HttpFetcher::with_cache`]. Only
+/// responses carrying an `ETag` or `Last-Modified` header are stored. The key
+/// is the full URL, date range and query included, so one cache can be
+/// shared between feeds and accounts.
+#[derive(Debug, Default)]
+pub struct ResponseCache {
+    entries: Mutex<BTreeMap<String, CachedResponse>>,
+}
+
+impl ResponseCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read a cache written by [`save`](Self::save)
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .context(format!("Failed to read response cache: {}", path.display()))?;
+        let entries = serde_json::from_str(&contents)
+            .context(format!("Invalid response cache: {}", path.display()))?;
+        Ok(Self {
+            entries: Mutex::new(entries),
+        })
+    }
+
+    /// Write the cache to `path` as JSON
+    ///
+    /// The keys are request URLs, credentials included, so keep the file private.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let json = serde_json::to_string(&*self.entries())
+            .context("Failed to serialize response cache")?;
+        std::fs::write(path, json).context(format!(
+            "Failed to write response cache: {}",
+            path.display()
+        ))
+    }
+
+    pub fn get(&self, url: &str) -> Option<CachedResponse> {
+        self.entries().get(url).cloned()
+    }
+
+    pub fn insert(&self, url: impl Into<String>, response: CachedResponse) {
+        self.entries().insert(url.into(), response);
+    }
+
+    fn entries(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, CachedResponse>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Which SOCS calendar feed to read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Feed {
+    /// The general school calendar, without sports fixtures
+    General,
+    /// Sports fixtures only
+    Sport,
+}
+
+impl Feed {
+    /// `query` with the `Sport` flag set for this feed
+    fn query(self, query: &CalendarQuery) -> CalendarQuery {
+        CalendarQuery {
+            sport: Some(self == Feed::Sport),
+            ..query.clone()
+        }
+    }
+}
+
+/// A SOCS calendar account that can read the general and sport feeds
+///
+/// Both feeds are served from the base URL, selected by the `Sport` flag,
+/// unless [`with_sport_url`](Self::with_sport_url) points the sport feed at
+/// a different `ashx` path.
+#[derive(Debug, Clone)]
+pub struct SocsClient {
+    client: reqwest::Client,
+    base_url: String,
+    sport_url: Option<String>,
+    query: CalendarQuery,
+}
+
+/// The calendar endpoint used when a config file doesn't name one
+const DEFAULT_BASE_URL: &str = "https://www.socscms.com/socs/xml/SOCScalendar.ashx";
+
+/// Credentials read by [`SocsClient::from_config`]
+#[derive(Debug, Deserialize)]
+struct SocsConfig {
+    id: String,
+    key: String,
+    base_url: Option<String>,
+}
+
+impl SocsClient {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.into(),
+            sport_url: None,
+            query: CalendarQuery::default(),
+        }
+    }
+
+    /// Build a client from a JSON config file holding the school's credentials
+    ///
+    /// The file looks like `{"id": "42", "key": "abc"}`, with an optional
+    /// `base_url` for installs that don't use the standard SOCS endpoint. The
+    /// `ID` and `key` parameters are added to that URL.
+    pub fn from_config(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .context(format!("Failed to read config file: {}", path.display()))?;
+        let config: SocsConfig = serde_json::from_str(&contents)
+            .context(format!("Invalid SOCS config file: {}", path.display()))?;
+
+        let base_url = config.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL);
+        let mut url = url::Url::parse(base_url)
+            .context(format!("Invalid base_url in config: {}", base_url))?;
+        url.query_pairs_mut()
+            .append_pair("ID", &config.id)
+            .append_pair("key", &config.key);
+        Ok(Self::new(url))
+    }
+
+    /// Read the sport feed from `sport_url` rather than the base URL
+    pub fn with_sport_url(mut self, sport_url: impl Into<String>) -> Self {
+        self.sport_url = Some(sport_url.into());
+        self
+    }
+
+    /// Send `query` on every request; its `Sport` flag is set per feed
+    pub fn with_query(mut self, query: CalendarQuery) -> Self {
+        self.query = query;
+        self
+    }
+
+    /// The request URL for one page of `feed` over a date range
+    pub fn feed_url(
+        &self,
+        feed: Feed,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<String> {
+        let fetcher = self.fetcher(feed);
+        build_calendar_url_with_query(&fetcher.base_url, start_date, end_date, &fetcher.query)
+    }
+
+    /// Fetch every event in `feed` between the given dates, paging as needed
+    pub async fn fetch(
+        &self,
+        feed: Feed,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<CalendarEvent>> {
+        let fetcher = self.fetcher(feed);
+        fetch_events_recursive_with_fetcher(
+            &fetcher,
+            start_date,
+            end_date,
+            &FetchOptions::default(),
+        )
+        .await
+        .map(|outcome| outcome.events)
+    }
+
+    /// Fetch several feeds and merge them, dropping events listed in more than one
+    pub async fn fetch_feeds(
+        &self,
+        feeds: &[Feed],
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<CalendarEvent>> {
+        let mut events = Vec::new();
+        for &feed in feeds {
+            events.extend(self.fetch(feed, start_date, end_date).await?);
+        }
+        dedup_and_sort(&mut events);
+        Ok(events)
+    }
+
+    fn fetcher(&self, feed: Feed) -> HttpFetcher {
+        let base_url = match (feed, &self.sport_url) {
+            (Feed::Sport, Some(sport_url)) => sport_url,
+            _ => &self.base_url,
+        };
+        HttpFetcher::with_client(self.client.clone(), base_url.as_str())
+            .with_query(feed.query(&self.query))
+    }
+}
 
 /// Fetch calendar data from the SOCS API
 pub async fn fetch_calendar(
@@ -7,26 +398,76 @@ pub async fn fetch_calendar(
     start_date: NaiveDate,
     end_date: NaiveDate,
 ) -> Result<String> {
-    // Format dates as "DD MMM YY" (e.g., "10 Dec 25")
-    let start_str = format_date_for_api(start_date);
-    let end_str = format_date_for_api(end_date);
+    let (body, _headers) = fetch_calendar_with_headers(base_url, start_date, end_date).await?;
+    Ok(body)
+}
 
-    // Build the URL with query parameters
-    let url = format!(
-        "{}&startdate={}&enddate={}&Sport=0&CoCurricular=0&IncludeInternal=1&IncludeUnpublished=1",
-        base_url,
-        urlencoding::encode(&start_str),
-        urlencoding::encode(&end_str)
-    );
+/// Fetch calendar data from the SOCS API along with the response headers
+///
+/// Useful for keeping hold of cache validators such as `ETag` and
+/// `Last-Modified` to send on a later conditional request.
+pub async fn fetch_calendar_with_headers(
+    base_url: &str,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<(String, HeaderMap)> {
+    fetch_calendar_with_client(&reqwest::Client::new(), base_url, start_date, end_date).await
+}
 
-    println!("Fetching calendar from: {}", url);
+/// Fetch calendar data and response headers using an existing HTTP client
+///
+/// Lets callers reuse one configured client (timeouts, connection pooling)
+/// across many requests.
+pub async fn fetch_calendar_with_client(
+    client: &reqwest::Client,
+    base_url: &str,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<(String, HeaderMap)> {
+    let url = build_calendar_url(base_url, start_date, end_date)?;
+    fetch_url(client, &url, None, None).await
+}
 
-    // Fetch the data
-    let response = reqwest::get(&url)
+/// Fetch calendar data, reusing `cache`'s copy when SOCS says it is unchanged
+///
+/// A range fetched before is requested with `If-None-Match` and
+/// `If-Modified-Since` from the stored response, and a `304 Not Modified`
+/// returns the stored body without downloading it again. New bodies replace
+/// the cached ones.
+///
+/// [`fetch_calendar`] keeps no state between calls, so it always downloads
+/// in full; conditional requests need the cache passed in here.
+pub async fn fetch_calendar_cached(
+    base_url: &str,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    cache: &ResponseCache,
+) -> Result<String> {
+    let url = build_calendar_url(base_url, start_date, end_date)?;
+    fetch_url_cached(&reqwest::Client::new(), &url, None, cache).await
+}
+
+/// Check whether the ID and key in `base_url` are accepted by SOCS
+///
+/// Makes a single-day request for today. Returns `Ok(true)` if SOCS answers
+/// with calendar XML (even an empty calendar), and `Ok(false)` if it rejects
+/// the credentials, either with a 401/403 or with a successful response that
+/// isn't calendar XML, such as an HTML error page. Network failures, invalid
+/// URLs and other HTTP errors are returned as `Err`.
+pub async fn validate_credentials(base_url: &str) -> Result<bool> {
+    let today = chrono::Local::now().date_naive();
+    let url = build_calendar_url(base_url, today, today)?;
+
+    let response = reqwest::Client::new()
+        .get(&url)
+        .send()
         .await
-        .context("Failed to fetch calendar data")?;
+        .context("Failed to reach SOCS")?;
 
     let status = response.status();
+    if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+        return Ok(false);
+    }
     if !status.is_success() {
         anyhow::bail!("HTTP request failed with status: {}", status);
     }
@@ -35,10 +476,209 @@ pub async fn fetch_calendar(
         .text()
         .await
         .context("Failed to read response body")?;
+    // serde-xml-rs reads any root element as an empty calendar, so spot HTML error pages first
+    if looks_like_html(&body) {
+        return Ok(false);
+    }
+    Ok(crate::parser::parse_calendar_xml(body).is_ok())
+}
+
+fn looks_like_html(body: &str) -> bool {
+    let start: String = body.trim_start().chars().take(256).collect();
+    let start = start.to_ascii_lowercase();
+    start.starts_with("<!doctype html") || start.contains("<html")
+}
 
+async fn fetch_url_cached(
+    client: &reqwest::Client,
+    url: &str,
+    max_response_bytes: Option<usize>,
+    cache: &ResponseCache,
+) -> Result<String> {
+    let cached = cache.get(url);
+    let (body, headers) = fetch_url(client, url, max_response_bytes, cached.as_ref()).await?;
+
+    let header = |name| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    };
+    let (etag, last_modified) = (header(ETAG), header(LAST_MODIFIED));
+    if etag.is_some() || last_modified.is_some() {
+        let response = CachedResponse {
+            body: body.clone(),
+            etag,
+            last_modified,
+        };
+        cache.insert(url, response);
+    }
     Ok(body)
 }
 
+/// Fetch `url`, sending `cached`'s validators and returning its body on a 304
+async fn fetch_url(
+    client: &reqwest::Client,
+    url: &str,
+    max_response_bytes: Option<usize>,
+    cached: Option<&CachedResponse>,
+) -> Result<(String, HeaderMap)> {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(url = %redact_key(url), "fetching calendar");
+
+    let mut request = client.get(url);
+    if let Some(cached) = cached {
+        if let Some(etag) = &cached.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &cached.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+
+    // Fetch the data
+    let mut response = request
+        .send()
+        .await
+        .context("Failed to fetch calendar data")?;
+
+    let status = response.status();
+    let headers = response.headers().clone();
+    if let (StatusCode::NOT_MODIFIED, Some(cached)) = (status, cached) {
+        return Ok((cached.body.clone(), headers));
+    }
+    if !status.is_success() {
+        anyhow::bail!("HTTP request failed with status: {}", status);
+    }
+
+    let Some(limit) = max_response_bytes else {
+        let body = response
+            .text()
+            .await
+            .context("Failed to read response body")?;
+        return Ok((body, headers));
+    };
+
+    // Read the body a chunk at a time so an oversized response is never held in full
+    if response
+        .content_length()
+        .is_some_and(|len| len > limit as u64)
+    {
+        return Err(ResponseTooLarge { limit }.into());
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .context("Failed to read response body")?
+    {
+        if body.len() + chunk.len() > limit {
+            return Err(ResponseTooLarge { limit }.into());
+        }
+        body.extend_from_slice(&chunk);
+    }
+    let body = String::from_utf8(body).context("Response body was not valid UTF-8")?;
+
+    Ok((body, headers))
+}
+
+/// `url` with the value of its `key` parameter hidden, for logging
+#[cfg(feature = "tracing")]
+fn redact_key(url: &str) -> String {
+    let Ok(mut parsed) = url::Url::parse(url) else {
+        return "<invalid url>".to_string();
+    };
+    let pairs: Vec<(String, String)> = parsed
+        .query_pairs()
+        .map(|(name, value)| {
+            if name.eq_ignore_ascii_case("key") {
+                (name.into_owned(), "REDACTED".to_string())
+            } else {
+                (name.into_owned(), value.into_owned())
+            }
+        })
+        .collect();
+    parsed.query_pairs_mut().clear().extend_pairs(pairs);
+    parsed.to_string()
+}
+
+/// Build the request URL for a date range on top of the caller's base URL
+///
+/// Any query parameters already on `base_url` are kept, except those this
+/// crate sets itself, which are replaced. Repeated keys are collapsed to their
+/// first occurrence, and stray `?`/`&` separators are dropped.
+pub fn build_calendar_url(
+    base_url: &str,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<String> {
+    build_calendar_url_with_query(base_url, start_date, end_date, &CalendarQuery::default())
+}
+
+/// Build the request URL for a date range using the given query parameters
+///
+/// Base URL parameters are handled as for [`build_calendar_url`]; one left
+/// out of `query` isn't overridden, so a value on the base URL is kept.
+pub fn build_calendar_url_with_query(
+    base_url: &str,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    query: &CalendarQuery,
+) -> Result<String> {
+    let mut url = url::Url::parse(base_url).context(format!("Invalid base URL: {}", base_url))?;
+
+    // Format dates as "DD MMM YY" (e.g., "10 Dec 25")
+    let mut params = vec![
+        ("startdate", format_date_for_api(start_date)),
+        ("enddate", format_date_for_api(end_date)),
+    ];
+    let flag = |value| query.bool_encoding.encode(value);
+    if let Some(sport) = query.sport {
+        params.push(("Sport", flag(sport)));
+    }
+    if let Some(co_curricular) = query.co_curricular {
+        params.push(("CoCurricular", flag(co_curricular)));
+    }
+    if let Some(include_internal) = query.include_internal {
+        params.push(("IncludeInternal", flag(include_internal)));
+    }
+    if let Some(include_unpublished) = query.include_unpublished {
+        params.push(("IncludeUnpublished", flag(include_unpublished)));
+    }
+    // Sending a key twice would leave SOCS to pick either value
+    if let Some((key, _)) = query
+        .extra
+        .iter()
+        .find(|(key, _)| params.iter().any(|(k, _)| k.eq_ignore_ascii_case(key)))
+    {
+        anyhow::bail!(
+            "Extra query parameter {} is already set by CalendarQuery",
+            key
+        );
+    }
+    params.extend(query.extra.iter().map(|(k, v)| (k.as_str(), v.clone())));
+
+    // SOCS treats query keys case-insensitively, so dedupe on that basis
+    let mut pairs: Vec<(String, String)> = Vec::new();
+    for (key, value) in url.query_pairs() {
+        let overridden = params.iter().any(|(k, _)| k.eq_ignore_ascii_case(&key));
+        let seen = pairs.iter().any(|(k, _)| k.eq_ignore_ascii_case(&key));
+        if !overridden && !seen {
+            pairs.push((key.into_owned(), value.into_owned()));
+        }
+    }
+    pairs.extend(params.into_iter().map(|(k, v)| (k.to_string(), v)));
+
+    let query = pairs
+        .iter()
+        .map(|(k, v)| format!("{}={}", urlencoding::encode(k), urlencoding::encode(v)))
+        .collect::<Vec<_>>()
+        .join("&");
+    url.set_query(Some(&query));
+
+    Ok(url.to_string())
+}
+
 /// Format a date for the SOCS API in "DD MMM YY" format (e.g., "10 Dec 25")
 fn format_date_for_api(date: NaiveDate) -> String {
     date.format("%d %b %y").to_string()
@@ -47,6 +687,7 @@ fn format_date_for_api(date: NaiveDate) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::{error_response, mock_server, ok_response};
 
     #[test]
     fn test_format_date_for_api() {
@@ -54,4 +695,297 @@ mod tests {
         let formatted = format_date_for_api(date);
         assert_eq!(formatted, "10 Dec 25");
     }
+
+    fn range() -> (NaiveDate, NaiveDate) {
+        (
+            NaiveDate::from_ymd_opt(2025, 12, 10).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 12, 31).unwrap(),
+        )
+    }
+
+    const EXPECTED_URL: &str = "https://www.socscms.com/socs/xml/SOCScalendar.ashx?ID=42&key=abc\
+        &startdate=10%20Dec%2025&enddate=31%20Dec%2025\
+        &Sport=0&CoCurricular=0&IncludeInternal=1&IncludeUnpublished=1";
+
+    #[test]
+    fn test_build_calendar_url_without_trailing_separator() {
+        let (start, end) = range();
+        let base = "https://www.socscms.com/socs/xml/SOCScalendar.ashx?ID=42&key=abc";
+        assert_eq!(build_calendar_url(base, start, end).unwrap(), EXPECTED_URL);
+    }
+
+    #[test]
+    fn test_build_calendar_url_with_trailing_ampersand() {
+        let (start, end) = range();
+        let base = "https://www.socscms.com/socs/xml/SOCScalendar.ashx?ID=42&key=abc&";
+        assert_eq!(build_calendar_url(base, start, end).unwrap(), EXPECTED_URL);
+    }
+
+    #[test]
+    fn test_build_calendar_url_with_trailing_question_mark() {
+        let (start, end) = range();
+        let base = "https://www.socscms.com/socs/xml/SOCScalendar.ashx?";
+        let url = build_calendar_url(base, start, end).unwrap();
+        assert!(url.starts_with("https://www.socscms.com/socs/xml/SOCScalendar.ashx?startdate="));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_calendar_with_headers_returns_etag() {
+        let server = mock_server(vec![
+            "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: 15\r\n\r\n<SOCSCalendar/>"
+                .to_string(),
+        ]);
+        let (start, end) = range();
+
+        let (body, headers) = fetch_calendar_with_headers(&server.url, start, end)
+            .await
+            .unwrap();
+        assert_eq!(body, "<SOCSCalendar/>");
+        assert_eq!(headers.get("etag").unwrap(), "\"v1\"");
+
+        let requests = server.requests.lock().unwrap();
+        assert!(
+            requests[0].starts_with("GET /socs/xml/SOCScalendar.ashx?ID=1&key=test&startdate=")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_calendar_cached_reuses_body_on_not_modified() {
+        let server = mock_server(vec![
+            "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: 15\r\nConnection: close\r\n\r\n<SOCSCalendar/>"
+                .to_string(),
+            "HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n".to_string(),
+        ]);
+        let (start, end) = range();
+        let cache = ResponseCache::new();
+
+        let first = fetch_calendar_cached(&server.url, start, end, &cache)
+            .await
+            .unwrap();
+        let second = fetch_calendar_cached(&server.url, start, end, &cache)
+            .await
+            .unwrap();
+        assert_eq!(first, "<SOCSCalendar/>");
+        assert_eq!(second, first);
+        let url = build_calendar_url(&server.url, start, end).unwrap();
+        assert_eq!(cache.get(&url).unwrap().etag.as_deref(), Some("\"v1\""));
+        let sport = CalendarQuery {
+            sport: Some(true),
+            ..CalendarQuery::default()
+        };
+        let sport_url = build_calendar_url_with_query(&server.url, start, end, &sport).unwrap();
+        assert_eq!(cache.get(&sport_url), None);
+
+        let requests = server.requests.lock().unwrap();
+        assert!(!requests[0].to_ascii_lowercase().contains("if-none-match"));
+        assert!(
+            requests[1]
+                .to_ascii_lowercase()
+                .contains("if-none-match: \"v1\"")
+        );
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_redact_key() {
+        let (start, end) = range();
+        let base = "https://www.socscms.com/socs/xml/SOCScalendar.ashx?ID=42&Key=abc";
+        let url = build_calendar_url(base, start, end).unwrap();
+        let redacted = redact_key(&url);
+        assert!(!redacted.contains("abc"));
+        assert!(redacted.contains("ID=42&Key=REDACTED&startdate="));
+    }
+
+    #[test]
+    fn test_build_calendar_url_replaces_existing_params() {
+        let (start, end) = range();
+        let base = "https://www.socscms.com/socs/xml/SOCScalendar.ashx?ID=42&key=abc&ID=7&StartDate=01%20Jan%2020&Sport=1";
+        assert_eq!(build_calendar_url(base, start, end).unwrap(), EXPECTED_URL);
+    }
+
+    #[test]
+    fn test_visibility_presets() {
+        let (start, end) = range();
+        let base = "https://www.socscms.com/socs/xml/SOCScalendar.ashx?ID=42&key=abc";
+        let cases = [
+            (
+                Visibility::PublicOnly,
+                "IncludeInternal=0&IncludeUnpublished=0",
+            ),
+            (Visibility::Staff, "IncludeInternal=1&IncludeUnpublished=0"),
+            (
+                Visibility::Everything,
+                "IncludeInternal=1&IncludeUnpublished=1",
+            ),
+        ];
+
+        for (visibility, expected) in cases {
+            let query = CalendarQuery::default().with_visibility(visibility);
+            let url = build_calendar_url_with_query(base, start, end, &query).unwrap();
+            assert!(url.ends_with(expected), "{visibility:?}: {url}");
+        }
+    }
+
+    #[test]
+    fn test_bool_encodings() {
+        let (start, end) = range();
+        let base = "https://www.socscms.com/socs/xml/SOCScalendar.ashx?ID=42&key=abc";
+        let dates = "&startdate=10%20Dec%2025&enddate=31%20Dec%2025";
+        let query = CalendarQuery::default().with_visibility(Visibility::Staff);
+
+        assert_eq!(
+            build_calendar_url_with_query(base, start, end, &query).unwrap(),
+            format!("{base}{dates}&Sport=0&CoCurricular=0&IncludeInternal=1&IncludeUnpublished=0")
+        );
+
+        let words = CalendarQuery {
+            bool_encoding: BoolEncoding::Word,
+            ..query
+        };
+        assert_eq!(
+            build_calendar_url_with_query(base, start, end, &words).unwrap(),
+            format!(
+                "{base}{dates}&Sport=false&CoCurricular=false\
+                 &IncludeInternal=true&IncludeUnpublished=false"
+            )
+        );
+    }
+
+    #[test]
+    fn test_feed_urls() {
+        let (start, end) = range();
+        let client =
+            SocsClient::new("https://www.socscms.com/socs/xml/SOCScalendar.ashx?ID=42&key=abc");
+
+        assert_eq!(
+            client.feed_url(Feed::General, start, end).unwrap(),
+            EXPECTED_URL
+        );
+        assert_eq!(
+            client.feed_url(Feed::Sport, start, end).unwrap(),
+            EXPECTED_URL.replace("Sport=0", "Sport=1")
+        );
+
+        let client =
+            client.with_sport_url("https://www.socscms.com/socs/xml/fixtures.ashx?ID=42&key=abc");
+        assert_eq!(
+            client.feed_url(Feed::Sport, start, end).unwrap(),
+            EXPECTED_URL
+                .replace("SOCScalendar.ashx", "fixtures.ashx")
+                .replace("Sport=0", "Sport=1")
+        );
+        assert_eq!(
+            client.feed_url(Feed::General, start, end).unwrap(),
+            EXPECTED_URL
+        );
+    }
+
+    #[test]
+    fn test_extra_query_params() {
+        let (start, end) = range();
+        let base = "https://www.socscms.com/socs/xml/SOCScalendar.ashx?ID=42&key=abc&yeargroup=7";
+        let query = CalendarQuery {
+            extra: vec![
+                ("YearGroup".to_string(), "10".to_string()),
+                ("Section".to_string(), "Prep & Pre-Prep".to_string()),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            build_calendar_url_with_query(base, start, end, &query).unwrap(),
+            format!("{EXPECTED_URL}&YearGroup=10&Section=Prep%20%26%20Pre-Prep")
+        );
+
+        let clashing = CalendarQuery {
+            extra: vec![("sport".to_string(), "1".to_string())],
+            ..Default::default()
+        };
+        assert!(build_calendar_url_with_query(base, start, end, &clashing).is_err());
+        let clashing_date = CalendarQuery {
+            extra: vec![("StartDate".to_string(), "01 Jan 26".to_string())],
+            ..Default::default()
+        };
+        assert!(build_calendar_url_with_query(base, start, end, &clashing_date).is_err());
+
+        let own_sport = CalendarQuery {
+            sport: None,
+            ..clashing
+        };
+        let url = build_calendar_url_with_query(base, start, end, &own_sport).unwrap();
+        assert!(url.ends_with("&CoCurricular=0&IncludeInternal=1&IncludeUnpublished=1&sport=1"));
+    }
+
+    #[test]
+    fn test_socs_client_from_config() {
+        let dir = std::env::temp_dir().join(format!("socs-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = dir.join("socs.json");
+        std::fs::write(&config, r#"{"id": "42", "key": "abc"}"#).unwrap();
+        let missing_key = dir.join("missing-key.json");
+        std::fs::write(&missing_key, r#"{"id": "42"}"#).unwrap();
+
+        let client = SocsClient::from_config(&config);
+        let missing = SocsClient::from_config(&missing_key);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let (start, end) = range();
+        assert_eq!(
+            client.unwrap().feed_url(Feed::General, start, end).unwrap(),
+            EXPECTED_URL
+        );
+        let error = format!("{:#}", missing.unwrap_err());
+        assert!(error.contains("missing field `key`"), "{error}");
+        assert!(SocsClient::from_config(dir.join("absent.json")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_validate_credentials() {
+        let server = mock_server(vec![
+            ok_response("<SOCSCalendar></SOCSCalendar>"),
+            error_response(403),
+            ok_response("<!DOCTYPE html><html><body>Invalid key</body></html>"),
+        ]);
+
+        assert!(validate_credentials(&server.url).await.unwrap());
+        assert!(!validate_credentials(&server.url).await.unwrap());
+        assert!(!validate_credentials(&server.url).await.unwrap());
+    }
+
+    #[test]
+    fn test_query_params_can_be_omitted() {
+        let (start, end) = range();
+        let base = "https://www.socscms.com/socs/xml/SOCScalendar.ashx?ID=42&key=abc";
+        let query = CalendarQuery {
+            sport: None,
+            co_curricular: None,
+            include_internal: None,
+            include_unpublished: None,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            build_calendar_url_with_query(base, start, end, &query).unwrap(),
+            "https://www.socscms.com/socs/xml/SOCScalendar.ashx?ID=42&key=abc\
+             &startdate=10%20Dec%2025&enddate=31%20Dec%2025"
+        );
+
+        let dates = "&startdate=10%20Dec%2025&enddate=31%20Dec%2025";
+        let internal_only = CalendarQuery {
+            include_internal: Some(true),
+            ..query.clone()
+        };
+        assert_eq!(
+            build_calendar_url_with_query(base, start, end, &internal_only).unwrap(),
+            format!("{base}{dates}&IncludeInternal=1")
+        );
+        let unpublished_only = CalendarQuery {
+            include_unpublished: Some(false),
+            ..query
+        };
+        assert_eq!(
+            build_calendar_url_with_query(base, start, end, &unpublished_only).unwrap(),
+            format!("{base}{dates}&IncludeUnpublished=0")
+        );
+    }
 }
@@ -0,0 +1,243 @@
+use crate::models::{CalendarEvent, EventId, EventTime};
+use std::collections::HashMap;
+
+/// The differences between two fetches of the same calendar
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CalendarDiff {
+    pub added: Vec<CalendarEvent>,
+    pub removed: Vec<CalendarEvent>,
+    pub changed: Vec<EventChange>,
+}
+
+/// An event present in both fetches whose details differ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventChange {
+    pub before: CalendarEvent,
+    pub after: CalendarEvent,
+}
+
+impl CalendarDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl EventChange {
+    /// Names of the fields that differ, in a fixed order
+    pub fn changed_fields(&self) -> Vec<&'static str> {
+        let (a, b) = (self.before.normalized(), self.after.normalized());
+        let mut fields = Vec::new();
+        if a.title != b.title {
+            fields.push("title");
+        }
+        if a.start != b.start {
+            fields.push("time");
+        }
+        if a.end != b.end {
+            fields.push("end");
+        }
+        if a.location != b.location {
+            fields.push("location");
+        }
+        if a.description != b.description {
+            fields.push("description");
+        }
+        if a.categories != b.categories {
+            fields.push("categories");
+        }
+        if a.priority != b.priority {
+            fields.push("priority");
+        }
+        if a.url != b.url {
+            fields.push("url");
+        }
+        if a.status != b.status {
+            fields.push("status");
+        }
+        if a.timezone != b.timezone {
+            fields.push("timezone");
+        }
+        if a.group != b.group {
+            fields.push("group");
+        }
+        fields
+    }
+}
+
+/// Compare two fetches of a calendar by event ID
+///
+/// When both copies of an event carry a [`modified`](CalendarEvent::modified)
+/// timestamp, it decides whether the event changed. Otherwise events are
+/// compared in their [`normalized`](CalendarEvent::normalized) form, so
+/// whitespace and category order differences aren't reported as changes.
+/// Each list in the result is in chronological order.
+pub fn diff_events(old: &[CalendarEvent], new: &[CalendarEvent]) -> CalendarDiff {
+    let old_by_id: HashMap<&EventId, &CalendarEvent> =
+        old.iter().map(|e| (&e.event_id, e)).collect();
+    let new_by_id: HashMap<&EventId, &CalendarEvent> =
+        new.iter().map(|e| (&e.event_id, e)).collect();
+
+    let mut diff = CalendarDiff::default();
+    for event in new {
+        match old_by_id.get(&event.event_id) {
+            None => diff.added.push(event.clone()),
+            Some(before) if has_changed(before, event) => diff.changed.push(EventChange {
+                before: (*before).clone(),
+                after: event.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+    diff.removed = old
+        .iter()
+        .filter(|e| !new_by_id.contains_key(&e.event_id))
+        .cloned()
+        .collect();
+
+    diff.added.sort_by(|a, b| a.chronological_cmp(b));
+    diff.removed.sort_by(|a, b| a.chronological_cmp(b));
+    diff.changed
+        .sort_by(|a, b| a.after.chronological_cmp(&b.after));
+    diff
+}
+
+fn has_changed(before: &CalendarEvent, after: &CalendarEvent) -> bool {
+    match (before.modified, after.modified) {
+        (Some(before), Some(after)) => before != after,
+        _ => before.normalized() != after.normalized(),
+    }
+}
+
+/// Render a diff as human-readable lines, e.g. for notification emails
+///
+/// Added events come first, then changed, then removed:
+///
+/// ```text
+/// + Added: 10 Dec at 08:30 — Chapel
+/// ~ Changed: 11 Dec at 09:00 — Assembly (time 08:30 → 09:00)
+/// - Removed: 12 Dec — INSET Day
+/// ```
+pub fn format_diff(diff: &CalendarDiff) -> String {
+    let mut lines = Vec::new();
+
+    for event in &diff.added {
+        lines.push(format!("+ Added: {}", summary(event)));
+    }
+    for change in &diff.changed {
+        let details: Vec<String> = change
+            .changed_fields()
+            .into_iter()
+            .map(|field| describe_change(field, &change.before, &change.after))
+            .collect();
+        if details.is_empty() {
+            lines.push(format!("~ Changed: {}", summary(&change.after)));
+        } else {
+            lines.push(format!(
+                "~ Changed: {} ({})",
+                summary(&change.after),
+                details.join(", ")
+            ));
+        }
+    }
+    for event in &diff.removed {
+        lines.push(format!("- Removed: {}", summary(event)));
+    }
+
+    lines.join("\n")
+}
+
+fn summary(event: &CalendarEvent) -> String {
+    format!("{} — {}", short_when(&event.start), event.title)
+}
+
+fn short_when(event_time: &EventTime) -> String {
+    match event_time {
+        EventTime::AllDay(date) => date.format("%d %b").to_string(),
+        EventTime::Specific { date, time } => {
+            format!("{} at {}", date.format("%d %b"), time.format("%H:%M"))
+        }
+    }
+}
+
+fn describe_change(field: &str, before: &CalendarEvent, after: &CalendarEvent) -> String {
+    let when = |a: &EventTime, b: &EventTime| match (a, b) {
+        (
+            EventTime::Specific { date: d1, time: t1 },
+            EventTime::Specific { date: d2, time: t2 },
+        ) if d1 == d2 => (
+            t1.format("%H:%M").to_string(),
+            t2.format("%H:%M").to_string(),
+        ),
+        _ => (short_when(a), short_when(b)),
+    };
+
+    let (from, to) = match field {
+        "title" => (before.title.clone(), after.title.clone()),
+        "time" => when(&before.start, &after.start),
+        "end" => when(&before.end, &after.end),
+        "location" => (before.location.clone(), after.location.clone()),
+        _ => return field.to_string(),
+    };
+    format!("{} {} → {}", field, from, to)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{all_day, date, event, timed};
+
+    #[test]
+    fn test_diff_and_format() {
+        let day = date(2025, 12, 10);
+        let mut assembly = event("1", timed(day, 8, 30), timed(day, 9, 0));
+        assembly.title = "Assembly".to_string();
+        let mut inset = all_day("2", date(2025, 12, 12));
+        inset.title = "INSET Day".to_string();
+        let old = vec![assembly.clone(), inset];
+
+        let mut moved = assembly;
+        moved.start = timed(day, 9, 0);
+        moved.end = timed(day, 9, 30);
+        let mut chapel = event("3", timed(day, 8, 0), timed(day, 8, 20));
+        chapel.title = "Chapel".to_string();
+        let new = vec![moved, chapel];
+
+        let diff = diff_events(&old, &new);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.changed[0].changed_fields(), vec!["time", "end"]);
+
+        assert_eq!(
+            format_diff(&diff),
+            "+ Added: 10 Dec at 08:00 — Chapel\n\
+             ~ Changed: 10 Dec at 09:00 — Assembly (time 08:30 → 09:00, end 09:00 → 09:30)\n\
+             - Removed: 12 Dec — INSET Day"
+        );
+    }
+
+    #[test]
+    fn test_diff_ignores_normalization_noise() {
+        let day = date(2025, 12, 10);
+        let old = vec![event("1", timed(day, 8, 30), timed(day, 9, 0))];
+        let mut new = old.clone();
+        new[0].title = format!("  {}  ", new[0].title);
+
+        assert!(diff_events(&old, &new).is_empty());
+    }
+
+    #[test]
+    fn test_diff_prefers_modified_timestamps() {
+        let day = date(2025, 12, 10);
+        let mut old = vec![event("1", timed(day, 8, 30), timed(day, 9, 0))];
+        old[0].modified = day.and_hms_opt(7, 0, 0);
+
+        let mut touched = old.clone();
+        touched[0].modified = day.and_hms_opt(7, 5, 0);
+        assert_eq!(diff_events(&old, &touched).changed.len(), 1);
+
+        let mut reworded = old.clone();
+        reworded[0].title = "Renamed".to_string();
+        assert!(diff_events(&old, &reworded).is_empty());
+    }
+}
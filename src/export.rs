@@ -0,0 +1,331 @@
+use crate::ics::to_ics;
+use crate::models::{CalendarEvent, EventTime};
+use anyhow::{Context, Result};
+use chrono::{Days, NaiveDate};
+use serde::Serialize;
+
+/// Renders a batch of events to a particular output format
+///
+/// Implementations are object safe, so an exporter can be picked at runtime
+/// as a `Box<dyn EventExporter>`.
+pub trait EventExporter {
+    fn export(&self, events: &[CalendarEvent]) -> Result<String>;
+}
+
+/// iCalendar output via [`to_ics`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IcsExporter;
+
+impl EventExporter for IcsExporter {
+    fn export(&self, events: &[CalendarEvent]) -> Result<String> {
+        Ok(to_ics(events))
+    }
+}
+
+/// CSV with a header row, one event per line
+///
+/// Dates are `YYYY-MM-DD` and times `HH:MM`, with the time columns left empty
+/// for all-day events. Categories are joined with `;`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CsvExporter;
+
+impl EventExporter for CsvExporter {
+    fn export(&self, events: &[CalendarEvent]) -> Result<String> {
+        let mut csv = String::from(
+            "event_id,title,start_date,start_time,end_date,end_time,location,categories,description\r\n",
+        );
+        for event in events {
+            let (start_date, start_time) = csv_time(&event.start);
+            let (end_date, end_time) = csv_time(&event.end);
+            let row = [
+                event.event_id.to_string(),
+                event.title.clone(),
+                start_date,
+                start_time,
+                end_date,
+                end_time,
+                event.location.clone(),
+                event.categories.join(";"),
+                event.description.clone().unwrap_or_default(),
+            ];
+            let row: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+            csv.push_str(&row.join(","));
+            csv.push_str("\r\n");
+        }
+        Ok(csv)
+    }
+}
+
+/// CSV in the column layout Outlook and Exchange import
+///
+/// Dates are `MM/DD/YYYY` and times `HH:MM AM/PM`, with the times left empty
+/// for all-day events and `All day event` written as `True`/`False`.
+pub fn to_outlook_csv(events: &[CalendarEvent]) -> Result<String> {
+    let mut csv = String::from(
+        "Subject,Start Date,Start Time,End Date,End Time,All day event,Location,Description\r\n",
+    );
+    for event in events {
+        let (start_date, start_time) = outlook_time(&event.start);
+        let (end_date, end_time) = outlook_time(&event.end);
+        let all_day = if event.start.is_all_day() {
+            "True"
+        } else {
+            "False"
+        };
+        let row = [
+            event.title.clone(),
+            start_date,
+            start_time,
+            end_date,
+            end_time,
+            all_day.to_string(),
+            event.location.clone(),
+            event.description.clone().unwrap_or_default(),
+        ];
+        let row: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&row.join(","));
+        csv.push_str("\r\n");
+    }
+    Ok(csv)
+}
+
+fn outlook_time(time: &EventTime) -> (String, String) {
+    let date = time.date().format("%m/%d/%Y").to_string();
+    match time {
+        EventTime::AllDay(_) => (date, String::new()),
+        EventTime::Specific { time, .. } => (date, time.format("%I:%M %p").to_string()),
+    }
+}
+
+fn csv_time(time: &EventTime) -> (String, String) {
+    let date = time.date().format("%Y-%m-%d").to_string();
+    match time {
+        EventTime::AllDay(_) => (date, String::new()),
+        EventTime::Specific { time, .. } => (date, time.format("%H:%M").to_string()),
+    }
+}
+
+/// Quote a field if it contains a comma, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// A JSON array in FullCalendar's event object format
+///
+/// All-day events use bare dates with an exclusive `end`, as FullCalendar
+/// expects; timed events use local `YYYY-MM-DDTHH:MM:SS` strings.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FullCalendarExporter;
+
+impl EventExporter for FullCalendarExporter {
+    fn export(&self, events: &[CalendarEvent]) -> Result<String> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Event<'a> {
+            id: &'a str,
+            title: &'a str,
+            start: String,
+            end: String,
+            all_day: bool,
+        }
+
+        let events: Vec<Event> = events
+            .iter()
+            .map(|event| Event {
+                id: event.event_id.as_str(),
+                title: &event.title,
+                start: full_calendar_time(&event.start, false),
+                end: full_calendar_time(&event.end, true),
+                all_day: event.start.is_all_day(),
+            })
+            .collect();
+        serde_json::to_string(&events).context("Failed to serialize events")
+    }
+}
+
+fn full_calendar_time(time: &EventTime, is_end: bool) -> String {
+    match time {
+        EventTime::AllDay(date) if is_end => date
+            .checked_add_days(Days::new(1))
+            .unwrap_or(*date)
+            .format("%Y-%m-%d")
+            .to_string(),
+        EventTime::AllDay(date) => date.format("%Y-%m-%d").to_string(),
+        EventTime::Specific { date, time } => {
+            date.and_time(*time).format("%Y-%m-%dT%H:%M:%S").to_string()
+        }
+    }
+}
+
+/// Column width used by [`render_week_ascii`]
+pub const DEFAULT_COLUMN_WIDTH: usize = 16;
+
+/// Render the week starting on `week_start` as a seven-column text grid
+///
+/// Uses [`DEFAULT_COLUMN_WIDTH`]; see [`render_week_ascii_with_width`].
+pub fn render_week_ascii(events: &[CalendarEvent], week_start: NaiveDate) -> String {
+    render_week_ascii_with_width(events, week_start, DEFAULT_COLUMN_WIDTH)
+}
+
+/// Render the week starting on `week_start` as a seven-column text grid
+///
+/// Each column is headed by its day, e.g. `Wed 10 Dec`. All-day events are
+/// listed in a band under the headers on every day they cover, and timed
+/// events below that on the day they start, as `HH:MM Title`. Text longer
+/// than `width` characters is cut short and ends with `…`. Events outside
+/// the week are left out.
+pub fn render_week_ascii_with_width(
+    events: &[CalendarEvent],
+    week_start: NaiveDate,
+    width: usize,
+) -> String {
+    let width = width.max(1);
+    let days: Vec<NaiveDate> = week_start.iter_days().take(7).collect();
+
+    let mut sorted: Vec<&CalendarEvent> = events.iter().collect();
+    sorted.sort_by(|a, b| a.chronological_cmp(b));
+
+    let mut all_day: Vec<Vec<String>> = vec![Vec::new(); 7];
+    let mut timed: Vec<Vec<String>> = vec![Vec::new(); 7];
+    for (column, day) in days.iter().enumerate() {
+        for event in &sorted {
+            match &event.start {
+                EventTime::AllDay(start) if *start <= *day && *day <= event.end.date() => {
+                    all_day[column].push(event.title.clone());
+                }
+                EventTime::Specific { date, time } if date == day => {
+                    timed[column].push(format!("{} {}", time.format("%H:%M"), event.title));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let border = format!("+{}\n", format!("{}+", "-".repeat(width)).repeat(7));
+    let row = |cells: Vec<String>| {
+        let cells: String = cells
+            .iter()
+            .map(|cell| format!("{:<width$}|", truncate_cell(cell, width)))
+            .collect();
+        format!("|{}\n", cells)
+    };
+    let band = |columns: &[Vec<String>]| {
+        let height = columns.iter().map(Vec::len).max().unwrap_or(0);
+        (0..height)
+            .map(|line| {
+                row(columns
+                    .iter()
+                    .map(|cells| cells.get(line).cloned().unwrap_or_default())
+                    .collect())
+            })
+            .collect::<String>()
+    };
+
+    let mut grid = border.clone();
+    grid.push_str(&row(days
+        .iter()
+        .map(|day| day.format("%a %d %b").to_string())
+        .collect()));
+    grid.push_str(&border);
+    if all_day.iter().any(|cells| !cells.is_empty()) {
+        grid.push_str(&band(&all_day));
+        grid.push_str(&border);
+    }
+    if timed.iter().any(|cells| !cells.is_empty()) {
+        grid.push_str(&band(&timed));
+        grid.push_str(&border);
+    }
+    grid
+}
+
+fn truncate_cell(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        text.to_string()
+    } else {
+        let mut cut: String = text.chars().take(width - 1).collect();
+        cut.push('…');
+        cut
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{all_day, date, event, timed};
+
+    #[test]
+    fn test_outlook_csv() {
+        let day = date(2025, 12, 10);
+        let mut inset = all_day("1", date(2025, 12, 1));
+        inset.title = "INSET Day".to_string();
+        let mut matinee = event("2", timed(day, 14, 0), timed(day, 16, 30));
+        matinee.location = "Theatre".to_string();
+
+        let csv = to_outlook_csv(&[inset, matinee]).unwrap();
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            rows[0],
+            "Subject,Start Date,Start Time,End Date,End Time,All day event,Location,Description"
+        );
+        assert_eq!(rows[1], "INSET Day,12/01/2025,,12/01/2025,,True,,");
+        assert_eq!(
+            rows[2],
+            "Event 2,12/10/2025,02:00 PM,12/10/2025,04:30 PM,False,Theatre,"
+        );
+    }
+
+    #[test]
+    fn test_exporters_behind_trait_object() {
+        let day = date(2025, 12, 10);
+        let mut chapel = event("1", timed(day, 8, 30), timed(day, 9, 0));
+        chapel.title = "Chapel, whole school".to_string();
+        let events = vec![chapel, all_day("2", date(2025, 12, 12))];
+
+        let exporters: Vec<Box<dyn EventExporter>> =
+            vec![Box::new(CsvExporter), Box::new(FullCalendarExporter)];
+        let outputs: Vec<String> = exporters
+            .iter()
+            .map(|exporter| exporter.export(&events).unwrap())
+            .collect();
+
+        let csv: Vec<&str> = outputs[0].lines().collect();
+        assert_eq!(csv.len(), 3);
+        assert_eq!(
+            csv[1],
+            "1,\"Chapel, whole school\",2025-12-10,08:30,2025-12-10,09:00,,,"
+        );
+        assert_eq!(csv[2], "2,Event 2,2025-12-12,,2025-12-12,,,,");
+
+        let json: serde_json::Value = serde_json::from_str(&outputs[1]).unwrap();
+        assert_eq!(json[0]["start"], "2025-12-10T08:30:00");
+        assert_eq!(json[0]["allDay"], false);
+        assert_eq!(json[1]["start"], "2025-12-12");
+        assert_eq!(json[1]["end"], "2025-12-13");
+        assert_eq!(json[1]["allDay"], true);
+    }
+
+    #[test]
+    fn test_render_week_ascii() {
+        let monday = date(2025, 12, 8);
+        let wednesday = date(2025, 12, 10);
+        let mut inset = all_day("1", monday);
+        inset.title = "INSET Day".to_string();
+        let mut concert = event("2", timed(wednesday, 19, 0), timed(wednesday, 21, 0));
+        concert.title = "Christmas Concert in the Great Hall".to_string();
+        let outside = all_day("3", date(2025, 12, 15));
+
+        let grid = render_week_ascii_with_width(&[concert, inset, outside], monday, 12);
+        let lines: Vec<&str> = grid.lines().collect();
+        assert_eq!(lines.len(), 7);
+        assert!(lines[1].starts_with("|Mon 08 Dec  |Tue 09 Dec  |Wed 10 Dec  |"));
+        assert!(lines[1].ends_with("|Sun 14 Dec  |"));
+        assert!(lines[3].starts_with("|INSET Day   |"));
+        assert!(lines[5].contains("|19:00 Chris…|"));
+        assert!(!grid.contains("Event 3"));
+        assert!(lines.iter().all(|line| line.chars().count() == 7 * 13 + 1));
+    }
+}
@@ -0,0 +1,1438 @@
+use crate::client::{CalendarFetcher, CalendarQuery, HttpFetcher, build_calendar_url_with_query};
+use crate::diff::{CalendarDiff, diff_events};
+use crate::filters::{all_day_only, sort_by_id};
+use crate::models::{CalendarEvent, EventId, local_today};
+use crate::parser::{parse_calendar_auto, parse_calendar_auto_with_meta};
+use anyhow::{Context, Result};
+use chrono::{Days, NaiveDate};
+use chrono_tz::Tz;
+use futures_util::stream::{self, Stream};
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How the end of a requested range is passed to SOCS
+///
+/// SOCS normally returns events on the `enddate` it is sent, so by default the
+/// end date goes out unchanged. Some installs have been seen leaving out
+/// events on that last day; [`Inclusive`](Self::Inclusive) works around this.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EndInclusivity {
+    /// Send the end date as given and trust SOCS to include it
+    #[default]
+    Exclusive,
+    /// Ask SOCS for one extra day, then drop events starting after the end date
+    Inclusive,
+}
+
+impl EndInclusivity {
+    /// The end date to put on the request for a range ending on `end_date`
+    fn request_end(self, end_date: NaiveDate) -> NaiveDate {
+        match self {
+            EndInclusivity::Exclusive => end_date,
+            EndInclusivity::Inclusive => end_date.succ_opt().unwrap_or(end_date),
+        }
+    }
+}
+
+/// Options controlling how [`fetch_events_recursive_with_options`] pages through a range
+#[derive(Debug, Clone)]
+pub struct FetchOptions {
+    /// Give up on a single page request after this long
+    pub timeout: Option<Duration>,
+    /// Record pages that fail and carry on past them rather than returning an error
+    pub skip_failed_pages: bool,
+    /// How many days to skip forward past a failed page
+    pub window_days: u32,
+    /// Fail a page with [`ResponseTooLarge`](crate::client::ResponseTooLarge)
+    /// rather than read a body bigger than this many bytes
+    pub max_response_bytes: Option<usize>,
+    /// Query parameters such as [`Visibility`](crate::client::Visibility) sent with each page
+    pub query: CalendarQuery,
+    /// Stop paging once this many events are known and return only the earliest ones
+    pub max_events: Option<usize>,
+    /// Whether to pad the requested end date to make sure events on it are included
+    pub end_inclusivity: EndInclusivity,
+    /// Split ranges longer than this many days into back-to-back sub-ranges,
+    /// each paged through separately, for installs that reject broad requests
+    pub max_span_days: Option<u32>,
+    /// Don't start another page once this moment has passed; a page already
+    /// in flight is allowed to finish
+    pub deadline: Option<Instant>,
+    /// Return an error when the deadline cuts the fetch short, rather than
+    /// the events gathered so far
+    pub fail_on_deadline: bool,
+    /// Accept any TLS certificate, including self-signed and expired ones
+    ///
+    /// This is insecure: anyone able to intercept the connection can read the
+    /// credentials in the URL and forge responses. Only turn it on to reach a
+    /// staging mirror during development.
+    pub danger_accept_invalid_certs: bool,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self {
+            timeout: None,
+            skip_failed_pages: false,
+            window_days: 7,
+            max_response_bytes: None,
+            query: CalendarQuery::default(),
+            max_events: None,
+            end_inclusivity: EndInclusivity::default(),
+            max_span_days: None,
+            deadline: None,
+            fail_on_deadline: false,
+            danger_accept_invalid_certs: false,
+        }
+    }
+}
+
+/// An inclusive date range skipped over after its page failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedRange {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    /// Why the page failed
+    pub reason: String,
+}
+
+/// Events gathered by a recursive fetch, plus any date ranges skipped over
+#[derive(Debug, Clone, Default)]
+pub struct FetchOutcome {
+    pub events: Vec<CalendarEvent>,
+    pub skipped: Vec<SkippedRange>,
+    /// Whether [`FetchOptions::deadline`] stopped paging before the range was covered
+    pub deadline_reached: bool,
+}
+
+// need to make a recursive function that takes in a start and end date. and fetches all events between those dates
+// it has to be recursive because the API ends due to size limits
+/// Recursively fetches all calendar events between the given start and end dates.
+///
+/// This function handles the limitation of the SOCS API which may truncate results due to size limits.
+/// It fetches events in chunks, starting from the given start date and continuing until all events
+/// within the date range are retrieved. The function automatically handles pagination by using
+/// the date of the last retrieved event as the starting point for the next request.
+///
+/// Events are deduplicated by ID and sorted by start time before being returned.
+///
+/// # Arguments
+///
+/// * `base_url` - The base URL for the SOCS calendar API which you are given when you create a key
+/// * `start_date` - The start date for the event range (inclusive)
+/// * `end_date` - The end date for the event range (inclusive)
+///
+/// # Returns
+///
+/// Returns a `Result` containing a vector of `CalendarEvent`s if successful, or an error if the
+/// fetching or parsing fails.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use chrono::NaiveDate;
+/// use socs_calendar_parser::fetch_events_recursive;
+///
+/// # async fn example() -> anyhow::Result<()> {
+/// let start = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+/// let end = NaiveDate::from_ymd_opt(2025, 12, 31).unwrap();
+/// let events = fetch_events_recursive("https://www.socscms.com/socs/xml/SOCScalendar.ashx?ID={}key={}", start, end).await?;
+/// println!("Found {} events", events.len());
+/// # Ok(())
+/// # }
+/// ```
+pub async fn fetch_events_recursive(
+    base_url: &str,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<Vec<CalendarEvent>> {
+    fetch_events_recursive_with_options(base_url, start_date, end_date, &FetchOptions::default())
+        .await
+        .map(|outcome| outcome.events)
+}
+
+/// Recursively fetches all calendar events between the given dates using the given options
+///
+/// Behaves like [`fetch_events_recursive`], but with
+/// [`FetchOptions::skip_failed_pages`] set a page that errors or times out is
+/// recorded in [`FetchOutcome::skipped`] and fetching resumes
+/// [`FetchOptions::window_days`] later, giving a best-effort result on flaky
+/// networks. With [`FetchOptions::max_events`] set, paging stops as soon as
+/// that many events are known and only the earliest of them are returned.
+/// With [`FetchOptions::deadline`] set, no new page is started once it has
+/// passed, and [`FetchOutcome::deadline_reached`] reports the cut-off.
+pub async fn fetch_events_recursive_with_options(
+    base_url: &str,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    options: &FetchOptions,
+) -> Result<FetchOutcome> {
+    let client = client_builder(options)
+        .build()
+        .context("Failed to build HTTP client")?;
+    let fetcher = HttpFetcher::with_client(client, base_url)
+        .with_query(options.query.clone())
+        .with_max_response_bytes(options.max_response_bytes);
+
+    fetch_events_recursive_with_fetcher(&fetcher, start_date, end_date, options).await
+}
+
+/// An HTTP client builder configured from the connection settings in `options`
+fn client_builder(options: &FetchOptions) -> reqwest::ClientBuilder {
+    let mut builder =
+        reqwest::Client::builder().danger_accept_invalid_certs(options.danger_accept_invalid_certs);
+    if let Some(timeout) = options.timeout {
+        builder = builder.timeout(timeout);
+    }
+    builder
+}
+
+/// Recursively fetches all calendar events between the given dates from any [`CalendarFetcher`]
+///
+/// This is the pagination loop behind [`fetch_events_recursive_with_options`],
+/// exposed so the source of each page can be swapped out.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip(fetcher)))]
+pub async fn fetch_events_recursive_with_fetcher<F: CalendarFetcher>(
+    fetcher: &F,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    options: &FetchOptions,
+) -> Result<FetchOutcome> {
+    let clip_to = end_date;
+    let end_date = options.end_inclusivity.request_end(end_date);
+
+    let mut all_events = Vec::new();
+    let mut skipped = Vec::new();
+    let mut deadline_reached = false;
+
+    'spans: for (span_start, span_end) in split_span(start_date, end_date, options.max_span_days) {
+        let end_date = span_end;
+        let mut current_start = span_start;
+
+        loop {
+            if options
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+            {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(
+                    cursor = %current_start,
+                    reason = "deadline",
+                    "stopping pagination"
+                );
+                if options.fail_on_deadline {
+                    anyhow::bail!("Fetch deadline passed before {} was reached", current_start);
+                }
+                deadline_reached = true;
+                break 'spans;
+            }
+
+            // fetch events
+            let page = fetcher
+                .fetch(current_start, end_date)
+                .await
+                .and_then(|body| parse_calendar_auto(&body));
+
+            let events = match page {
+                Ok(events) => events,
+                Err(error) if options.skip_failed_pages => {
+                    let skip_end = current_start
+                        .checked_add_days(Days::new(options.window_days.max(1) as u64 - 1))
+                        .map_or(end_date, |date| date.min(end_date));
+
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(
+                        cursor = %current_start,
+                        skip_end = %skip_end,
+                        error = %error,
+                        "skipping failed page"
+                    );
+
+                    skipped.push(SkippedRange {
+                        start: current_start,
+                        end: skip_end,
+                        reason: format!("{:#}", error),
+                    });
+                    match skip_end.succ_opt() {
+                        Some(next) if next <= end_date => {
+                            current_start = next;
+                            continue;
+                        }
+                        _ => {
+                            #[cfg(feature = "tracing")]
+                            tracing::debug!(
+                                cursor = %current_start,
+                                reason = "skipped_to_end",
+                                "stopping pagination"
+                            );
+                            break;
+                        }
+                    }
+                }
+                Err(error) => return Err(error),
+            };
+
+            let next = next_cursor(&events, current_start, end_date);
+            all_events.extend(events);
+
+            match next {
+                Some(next) if has_enough_before(&all_events, next, options.max_events) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(cursor = %next, reason = "max_events", "stopping pagination");
+                    break 'spans;
+                }
+                Some(next) => current_start = next,
+                None => break,
+            }
+        }
+
+        // Everything up to the end of a finished span is known
+        let after_span = span_end.succ_opt().unwrap_or(span_end);
+        if has_enough_before(&all_events, after_span, options.max_events) {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(cursor = %after_span, reason = "max_events", "stopping pagination");
+            break;
+        }
+    }
+
+    dedup_and_sort(&mut all_events);
+    if options.end_inclusivity == EndInclusivity::Inclusive {
+        all_events.retain(|event| event.start.date() <= clip_to);
+    }
+    if let Some(max_events) = options.max_events {
+        all_events.truncate(max_events);
+    }
+
+    Ok(FetchOutcome {
+        events: all_events,
+        skipped,
+        deadline_reached,
+    })
+}
+
+/// Recursively fetches events like [`fetch_events_recursive`], also returning each raw page
+///
+/// The second element holds every response body exactly as the API returned
+/// it, in fetch order, so it can be archived and re-parsed later.
+pub async fn fetch_events_recursive_with_raw(
+    base_url: &str,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<(Vec<CalendarEvent>, Vec<String>)> {
+    let fetcher = HttpFetcher::new(base_url);
+    fetch_with_raw_pages(&fetcher, start_date, end_date, &FetchOptions::default())
+        .await
+        .map(|(outcome, pages)| (outcome.events, pages))
+}
+
+async fn fetch_with_raw_pages<F: CalendarFetcher + Sync>(
+    fetcher: &F,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    options: &FetchOptions,
+) -> Result<(FetchOutcome, Vec<String>)> {
+    let recorder = RawPageRecorder {
+        inner: fetcher,
+        pages: Mutex::new(Vec::new()),
+    };
+    let outcome =
+        fetch_events_recursive_with_fetcher(&recorder, start_date, end_date, options).await?;
+    let pages = recorder
+        .pages
+        .into_inner()
+        .unwrap_or_else(|e| e.into_inner());
+    Ok((outcome, pages))
+}
+
+/// Passes requests through to another fetcher, keeping a copy of each body it returns
+struct RawPageRecorder<'a, F> {
+    inner: &'a F,
+    pages: Mutex<Vec<String>>,
+}
+
+impl<F: CalendarFetcher + Sync> CalendarFetcher for RawPageRecorder<'_, F> {
+    async fn fetch(&self, start_date: NaiveDate, end_date: NaiveDate) -> Result<String> {
+        let body = self.inner.fetch(start_date, end_date).await?;
+        self.pages
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(body.clone());
+        Ok(body)
+    }
+}
+
+/// How many times [`fetch_events_best_effort`] tries each page before giving up
+const BEST_EFFORT_ATTEMPTS: u32 = 3;
+
+/// A page request that still failed after being retried
+#[derive(Debug)]
+pub struct SocsError {
+    /// The range the failed request asked for
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    source: anyhow::Error,
+}
+
+impl fmt::Display for SocsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Failed to fetch events from {} to {}: {:#}",
+            self.start, self.end, self.source
+        )
+    }
+}
+
+impl std::error::Error for SocsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+/// Recursively fetch events, salvaging what was gathered if a page keeps failing
+///
+/// Each page is tried up to three times, with a short pause between attempts.
+/// If one still fails, paging stops there and the events from the pages
+/// already fetched are returned alongside the error, rather than discarded.
+/// Unlike [`FetchOptions::skip_failed_pages`], nothing past the failed page
+/// is fetched.
+pub async fn fetch_events_best_effort(
+    base_url: &str,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> (Vec<CalendarEvent>, Option<SocsError>) {
+    let fetcher = HttpFetcher::new(base_url);
+    best_effort_with_fetcher(&fetcher, start_date, end_date, Duration::from_millis(500)).await
+}
+
+async fn best_effort_with_fetcher<F: CalendarFetcher + Sync>(
+    fetcher: &F,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    retry_delay: Duration,
+) -> (Vec<CalendarEvent>, Option<SocsError>) {
+    let retrying = RetryingFetcher {
+        inner: fetcher,
+        retry_delay,
+        last_request: Mutex::new((start_date, end_date)),
+    };
+    let recorder = RawPageRecorder {
+        inner: &retrying,
+        pages: Mutex::new(Vec::new()),
+    };
+
+    let outcome = fetch_events_recursive_with_fetcher(
+        &recorder,
+        start_date,
+        end_date,
+        &FetchOptions::default(),
+    )
+    .await;
+    match outcome {
+        Ok(outcome) => (outcome.events, None),
+        Err(source) => {
+            let pages = recorder
+                .pages
+                .into_inner()
+                .unwrap_or_else(|e| e.into_inner());
+            let mut events: Vec<CalendarEvent> = pages
+                .iter()
+                .filter_map(|page| parse_calendar_auto(page).ok())
+                .flatten()
+                .collect();
+            dedup_and_sort(&mut events);
+
+            let (start, end) = retrying
+                .last_request
+                .into_inner()
+                .unwrap_or_else(|e| e.into_inner());
+            (events, Some(SocsError { start, end, source }))
+        }
+    }
+}
+
+/// Retries failed requests on another fetcher, remembering the range last asked for
+struct RetryingFetcher<'a, F> {
+    inner: &'a F,
+    retry_delay: Duration,
+    last_request: Mutex<(NaiveDate, NaiveDate)>,
+}
+
+impl<F: CalendarFetcher + Sync> CalendarFetcher for RetryingFetcher<'_, F> {
+    async fn fetch(&self, start_date: NaiveDate, end_date: NaiveDate) -> Result<String> {
+        *self.last_request.lock().unwrap_or_else(|e| e.into_inner()) = (start_date, end_date);
+        let mut attempt = 1;
+        loop {
+            match self.inner.fetch(start_date, end_date).await {
+                Err(_) if attempt < BEST_EFFORT_ATTEMPTS => {
+                    attempt += 1;
+                    tokio::time::sleep(self.retry_delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Count the distinct events between the given dates
+///
+/// If the first page carries a `<Header>` with a total count, that count is
+/// returned straight away without fetching the remaining pages. Otherwise
+/// this pages through the range like [`fetch_events_recursive`] and counts
+/// the events after deduplication.
+pub async fn count_events(
+    base_url: &str,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<usize> {
+    count_events_with_fetcher(&HttpFetcher::new(base_url), start_date, end_date).await
+}
+
+async fn count_events_with_fetcher<F: CalendarFetcher + Sync>(
+    fetcher: &F,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<usize> {
+    let first = fetcher.fetch(start_date, end_date).await?;
+    if let Ok((_, Some(meta))) = parse_calendar_auto_with_meta(&first)
+        && let Some(total) = meta.total_count
+    {
+        return Ok(total);
+    }
+
+    // Serve the page already fetched rather than asking for it again
+    let primed = PrimedFetcher {
+        first: Mutex::new(Some(first)),
+        inner: fetcher,
+    };
+    fetch_events_recursive_with_fetcher(&primed, start_date, end_date, &FetchOptions::default())
+        .await
+        .map(|outcome| outcome.events.len())
+}
+
+/// Answers the first request with a page fetched up front, then defers to another fetcher
+struct PrimedFetcher<'a, F> {
+    first: Mutex<Option<String>>,
+    inner: &'a F,
+}
+
+impl<F: CalendarFetcher + Sync> CalendarFetcher for PrimedFetcher<'_, F> {
+    async fn fetch(&self, start_date: NaiveDate, end_date: NaiveDate) -> Result<String> {
+        let first = self.first.lock().unwrap_or_else(|e| e.into_inner()).take();
+        match first {
+            Some(body) => Ok(body),
+            None => self.inner.fetch(start_date, end_date).await,
+        }
+    }
+}
+
+/// Preview the URLs a recursive fetch over the given dates would request, without sending them
+///
+/// This is only an approximation: the real fetcher moves on from wherever
+/// each page's last event falls, which can't be known in advance, so the
+/// preview assumes each page advances by [`FetchOptions::window_days`]. As
+/// with the real fetcher, every request runs to `end_date`, adjusted for
+/// [`FetchOptions::end_inclusivity`].
+pub fn preview_urls(
+    base_url: &str,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    options: &FetchOptions,
+) -> Result<Vec<String>> {
+    let end_date = options.end_inclusivity.request_end(end_date);
+    let step = Days::new(options.window_days.max(1).into());
+    let mut urls = Vec::new();
+    let mut cursor = Some(start_date);
+    while let Some(current) = cursor.filter(|date| *date <= end_date) {
+        urls.push(build_calendar_url_with_query(
+            base_url,
+            current,
+            end_date,
+            &options.query,
+        )?);
+        cursor = current.checked_add_days(step);
+    }
+    Ok(urls)
+}
+
+/// Lazily fetches calendar events between the given dates as a stream
+///
+/// Pages are only requested as the stream is polled and their events are
+/// yielded straight away, holding just the set of IDs already seen so that
+/// duplicates from overlapping pages are dropped on the fly. As nothing is
+/// buffered, ordering is page-local: events arrive in the order the API
+/// returned them rather than globally sorted as with
+/// [`fetch_events_recursive`]. The stream ends after yielding an error.
+pub fn fetch_events_recursive_stream(
+    base_url: &str,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> impl Stream<Item = Result<CalendarEvent>> + '_ {
+    struct State {
+        fetcher: HttpFetcher,
+        cursor: Option<NaiveDate>,
+        seen: HashSet<EventId>,
+        pending: VecDeque<CalendarEvent>,
+    }
+
+    let state = State {
+        fetcher: HttpFetcher::new(base_url),
+        cursor: Some(start_date),
+        seen: HashSet::new(),
+        pending: VecDeque::new(),
+    };
+
+    stream::unfold(state, move |mut state| async move {
+        loop {
+            if let Some(event) = state.pending.pop_front() {
+                return Some((Ok(event), state));
+            }
+
+            let cursor = state.cursor?;
+            let page = state
+                .fetcher
+                .fetch(cursor, end_date)
+                .await
+                .and_then(|body| parse_calendar_auto(&body));
+
+            match page {
+                Ok(events) => {
+                    state.cursor = next_cursor(&events, cursor, end_date);
+                    let unseen = events
+                        .into_iter()
+                        .filter(|e| state.seen.insert(e.event_id.clone()));
+                    state.pending.extend(unseen);
+                }
+                Err(error) => {
+                    state.cursor = None;
+                    return Some((Err(error), state));
+                }
+            }
+        }
+    })
+}
+
+/// Fetch a rolling window of events covering `from` and the following `days` days
+///
+/// The window is inclusive, so `days = 0` fetches just `from` itself and
+/// `days = 7` fetches eight days in total.
+pub async fn fetch_events_window(
+    base_url: &str,
+    from: NaiveDate,
+    days: u32,
+) -> Result<Vec<CalendarEvent>> {
+    fetch_events_recursive(base_url, from, window_end(from, days)?).await
+}
+
+/// The inclusive last day of a window starting at `from`
+fn window_end(from: NaiveDate, days: u32) -> Result<NaiveDate> {
+    from.checked_add_days(Days::new(days.into()))
+        .context(format!(
+            "Window of {} days from {} is out of range",
+            days, from
+        ))
+}
+
+/// Fetch today's events, taking "today" from the school's time zone
+///
+/// Uses [`local_today`] so a server running in UTC doesn't ask for the wrong
+/// day near midnight.
+pub async fn fetch_today(base_url: &str, tz: Tz) -> Result<Vec<CalendarEvent>> {
+    let today = local_today(tz);
+    fetch_events_recursive(base_url, today, today).await
+}
+
+/// Re-fetch a range every `interval`, calling `on_change` whenever it differs from the last fetch
+///
+/// The first fetch only sets the baseline, so `on_change` is called with
+/// non-empty [`CalendarDiff`]s from the second poll onwards. This runs until a
+/// fetch fails, and then returns that error.
+pub async fn watch(
+    base_url: &str,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    interval: Duration,
+    mut on_change: impl FnMut(CalendarDiff),
+) -> Result<()> {
+    let fetcher = HttpFetcher::new(base_url);
+    let options = FetchOptions::default();
+
+    let mut previous =
+        fetch_events_recursive_with_fetcher(&fetcher, start_date, end_date, &options)
+            .await?
+            .events;
+    loop {
+        tokio::time::sleep(interval).await;
+        let current = fetch_events_recursive_with_fetcher(&fetcher, start_date, end_date, &options)
+            .await?
+            .events;
+
+        let diff = diff_events(&previous, &current);
+        if !diff.is_empty() {
+            on_change(diff);
+        }
+        previous = current;
+    }
+}
+
+/// An event along with the name of the calendar it came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourcedEvent {
+    pub source: String,
+    pub event: CalendarEvent,
+}
+
+/// Fetch several schools' calendars over the same dates, tagging each event with its source
+///
+/// `sources` pairs a display name with each base URL. Events are
+/// deduplicated by ID within each source only, since different schools may
+/// reuse IDs, then merged chronologically with ties broken by source name.
+pub async fn fetch_events_multi_tagged(
+    sources: &[(String, String)],
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<Vec<SourcedEvent>> {
+    let mut all_events = Vec::new();
+    for (name, base_url) in sources {
+        let events = fetch_events_recursive(base_url, start_date, end_date)
+            .await
+            .context(format!("Failed to fetch calendar for {}", name))?;
+        all_events.extend(events.into_iter().map(|event| SourcedEvent {
+            source: name.clone(),
+            event,
+        }));
+    }
+
+    all_events.sort_by(|a, b| {
+        a.event
+            .chronological_cmp(&b.event)
+            .then_with(|| a.source.cmp(&b.source))
+    });
+    Ok(all_events)
+}
+
+/// Fetch events across several explicit date ranges in one go
+///
+/// Each range is fetched recursively in turn, then the results are merged,
+/// deduplicated by ID and sorted, so overlapping or adjacent ranges don't
+/// produce duplicate events.
+pub async fn fetch_events_ranges(
+    base_url: &str,
+    ranges: &[(NaiveDate, NaiveDate)],
+) -> Result<Vec<CalendarEvent>> {
+    let mut all_events = Vec::new();
+    for &(start_date, end_date) in ranges {
+        all_events.extend(fetch_events_recursive(base_url, start_date, end_date).await?);
+    }
+
+    dedup_and_sort(&mut all_events);
+    Ok(all_events)
+}
+
+/// Fetch each academic term's dates and group the events by term name
+///
+/// `terms` pairs a name such as "Michaelmas" with the term's first and last
+/// day. An event that runs across a boundary is returned by more than one
+/// term's fetch, but is only filed under the term containing its start. One
+/// that starts outside every term stays with the first term it was fetched
+/// for. Every term gets an entry, even if it has no events.
+pub async fn fetch_by_terms(
+    base_url: &str,
+    terms: &[(String, NaiveDate, NaiveDate)],
+) -> Result<BTreeMap<String, Vec<CalendarEvent>>> {
+    let mut fetched: Vec<(&str, CalendarEvent)> = Vec::new();
+    for (name, start_date, end_date) in terms {
+        let events = fetch_events_recursive(base_url, *start_date, *end_date)
+            .await
+            .context(format!("Failed to fetch events for term {}", name))?;
+        fetched.extend(events.into_iter().map(|event| (name.as_str(), event)));
+    }
+
+    let mut by_term: BTreeMap<String, Vec<CalendarEvent>> = terms
+        .iter()
+        .map(|(name, _, _)| (name.clone(), Vec::new()))
+        .collect();
+    let mut seen = HashSet::new();
+    for (fetched_for, event) in fetched {
+        if !seen.insert(event.event_id.clone()) {
+            continue;
+        }
+        let start = event.start.date();
+        let term = terms
+            .iter()
+            .find(|(_, first, last)| (*first..=*last).contains(&start))
+            .map_or(fetched_for, |(name, _, _)| name.as_str());
+        by_term.entry(term.to_string()).or_default().push(event);
+    }
+    for events in by_term.values_mut() {
+        events.sort_by(|a, b| a.chronological_cmp(b));
+    }
+    Ok(by_term)
+}
+
+/// Deduplicate events by ID, then sort them chronologically
+///
+/// When copies of an event differ, the one that sorts first by
+/// [`CalendarEvent::content_cmp`] is kept, so the survivor doesn't depend on
+/// the order pages arrived in.
+pub(crate) fn dedup_and_sort(events: &mut Vec<CalendarEvent>) {
+    // sort_by_id is stable, so presorting by content decides each ID's survivor
+    events.sort_by(|a, b| a.content_cmp(b));
+    sort_by_id(events);
+    events.dedup_by(|a, b| a.event_id == b.event_id);
+    events.sort_by(|a, b| a.chronological_cmp(b));
+}
+
+/// Split `start..=end` into consecutive inclusive spans of at most `max_days` days
+fn split_span(
+    start: NaiveDate,
+    end: NaiveDate,
+    max_days: Option<u32>,
+) -> Vec<(NaiveDate, NaiveDate)> {
+    let Some(max_days) = max_days else {
+        return vec![(start, end)];
+    };
+    let mut spans = Vec::new();
+    let mut span_start = Some(start);
+    while let Some(current) = span_start.filter(|date| *date <= end) {
+        let span_end = current
+            .checked_add_days(Days::new(u64::from(max_days.max(1)) - 1))
+            .map_or(end, |date| date.min(end));
+        spans.push((current, span_end));
+        span_start = span_end.succ_opt();
+    }
+    spans
+}
+
+/// Whether at least `max_events` distinct events start before `cursor`
+///
+/// Later pages start on `cursor`, so only events before it are known to be
+/// complete; anything on the cursor day may still have earlier siblings to come.
+fn has_enough_before(
+    events: &[CalendarEvent],
+    cursor: NaiveDate,
+    max_events: Option<usize>,
+) -> bool {
+    let Some(max_events) = max_events else {
+        return false;
+    };
+    let complete: HashSet<&EventId> = events
+        .iter()
+        .filter(|e| e.start.date() < cursor)
+        .map(|e| &e.event_id)
+        .collect();
+    complete.len() >= max_events
+}
+
+/// Work out where the next page should start, or `None` if pagination is done
+fn next_cursor(
+    events: &[CalendarEvent],
+    cursor: NaiveDate,
+    end_date: NaiveDate,
+) -> Option<NaiveDate> {
+    let Some(last) = events.last() else {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(cursor = %cursor, reason = "empty_page", "stopping pagination");
+        return None;
+    };
+    let last_event_date = last.start.date();
+
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        cursor = %cursor,
+        count = events.len(),
+        first = %events[0].start.date(),
+        last = %last_event_date,
+        "fetched page"
+    );
+
+    // Stop if we've reached the end date
+    if last_event_date >= end_date {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(last = %last_event_date, reason = "reached_end", "stopping pagination");
+        return None;
+    }
+
+    // Stop if the page didn't move past the current cursor, otherwise the
+    // next request would be identical and we'd loop forever
+    if last_event_date <= cursor {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(cursor = %cursor, reason = "no_progress", "stopping pagination");
+        return None;
+    }
+
+    // Continue from the same day as the last event to avoid missing events
+    // The deduplication will handle any duplicates
+    #[cfg(feature = "tracing")]
+    tracing::debug!(next_cursor = %last_event_date, "continuing pagination");
+    Some(last_event_date)
+}
+
+/// Fetch only the all-day events between the given start and end dates
+///
+/// The SOCS API has no all-day filter, so this still downloads every event in
+/// the range via [`fetch_events_recursive`] and filters them afterwards.
+pub async fn fetch_all_day(
+    base_url: &str,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<Vec<CalendarEvent>> {
+    let events = fetch_events_recursive(base_url, start_date, end_date).await?;
+    Ok(all_day_only(events))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::ResponseTooLarge;
+    use crate::test_utils::{all_day, date, error_response, mock_server, ok_response};
+    use futures_util::StreamExt;
+
+    /// Serves scripted pages in order, recording the range each was requested for
+    struct ScriptedFetcher {
+        pages: Mutex<VecDeque<Result<String>>>,
+        requests: Mutex<Vec<(NaiveDate, NaiveDate)>>,
+    }
+
+    impl ScriptedFetcher {
+        fn new(pages: Vec<Result<String>>) -> Self {
+            Self {
+                pages: Mutex::new(pages.into()),
+                requests: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    impl CalendarFetcher for ScriptedFetcher {
+        async fn fetch(&self, start_date: NaiveDate, end_date: NaiveDate) -> Result<String> {
+            self.requests.lock().unwrap().push((start_date, end_date));
+            self.pages
+                .lock()
+                .unwrap()
+                .pop_front()
+                .unwrap_or_else(|| Err(anyhow::anyhow!("No more scripted pages")))
+        }
+    }
+
+    fn page_xml(events: &[(&str, &str)]) -> String {
+        let events: String = events
+            .iter()
+            .map(|(id, day)| {
+                format!(
+                    "<CalendarEvent><EventID>{}</EventID>\
+                     <StartDate>{}</StartDate><EndDate>{}</EndDate>\
+                     <StartTime>All Day</StartTime><Title>Event {}</Title>\
+                     <Location></Location><Category></Category></CalendarEvent>",
+                    id, day, day, id
+                )
+            })
+            .collect();
+        format!("<SOCSCalendar>{}</SOCSCalendar>", events)
+    }
+
+    fn page(events: &[(&str, &str)]) -> String {
+        ok_response(&page_xml(events))
+    }
+
+    #[test]
+    fn test_danger_accept_invalid_certs_is_wired_to_client() {
+        // reqwest's builder only lists the setting in its Debug output once enabled
+        let flagged = |options: &FetchOptions| {
+            format!("{:?}", client_builder(options)).contains("danger_accept_invalid_certs")
+        };
+        assert!(!flagged(&FetchOptions::default()));
+
+        let options = FetchOptions {
+            danger_accept_invalid_certs: true,
+            ..FetchOptions::default()
+        };
+        assert!(flagged(&options));
+    }
+
+    #[tokio::test]
+    async fn test_pagination_with_scripted_fetcher() {
+        let fetcher = ScriptedFetcher::new(vec![
+            Ok(page_xml(&[("1", "01/12/2025"), ("2", "05/12/2025")])),
+            Ok(page_xml(&[("2", "05/12/2025"), ("3", "12/12/2025")])),
+            Ok(page_xml(&[("3", "12/12/2025"), ("4", "31/12/2025")])),
+        ]);
+
+        let outcome = fetch_events_recursive_with_fetcher(
+            &fetcher,
+            date(2025, 12, 1),
+            date(2025, 12, 31),
+            &FetchOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        let ids: Vec<&str> = outcome.events.iter().map(|e| e.event_id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "2", "3", "4"]);
+
+        let starts: Vec<NaiveDate> = fetcher
+            .requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(start, _)| *start)
+            .collect();
+        assert_eq!(
+            starts,
+            vec![date(2025, 12, 1), date(2025, 12, 5), date(2025, 12, 12)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_raw_pages_returned_untouched() {
+        let pages = vec![
+            page_xml(&[("1", "01/12/2025"), ("2", "05/12/2025")]),
+            format!(
+                "<?xml version=\"1.0\"?>\n  {}  \n",
+                page_xml(&[("2", "05/12/2025"), ("3", "31/12/2025")])
+            ),
+        ];
+        let fetcher = ScriptedFetcher::new(pages.iter().cloned().map(Ok).collect());
+
+        let (outcome, raw) = fetch_with_raw_pages(
+            &fetcher,
+            date(2025, 12, 1),
+            date(2025, 12, 31),
+            &FetchOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(raw, pages);
+        assert_eq!(outcome.events.len(), 3);
+    }
+
+    /// Takes `delay` to answer each request
+    struct SlowFetcher {
+        inner: ScriptedFetcher,
+        delay: Duration,
+    }
+
+    impl CalendarFetcher for SlowFetcher {
+        async fn fetch(&self, start_date: NaiveDate, end_date: NaiveDate) -> Result<String> {
+            tokio::time::sleep(self.delay).await;
+            self.inner.fetch(start_date, end_date).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deadline_returns_early() {
+        let pages = || {
+            vec![
+                Ok(page_xml(&[("1", "01/12/2025"), ("2", "05/12/2025")])),
+                Ok(page_xml(&[("2", "05/12/2025"), ("3", "12/12/2025")])),
+                Ok(page_xml(&[("3", "12/12/2025"), ("4", "31/12/2025")])),
+            ]
+        };
+        let fetcher = SlowFetcher {
+            inner: ScriptedFetcher::new(pages()),
+            delay: Duration::from_millis(50),
+        };
+        let mut options = FetchOptions {
+            deadline: Some(Instant::now() + Duration::from_millis(20)),
+            ..Default::default()
+        };
+
+        let outcome = fetch_events_recursive_with_fetcher(
+            &fetcher,
+            date(2025, 12, 1),
+            date(2025, 12, 31),
+            &options,
+        )
+        .await
+        .unwrap();
+        assert!(outcome.deadline_reached);
+        let ids: Vec<&str> = outcome.events.iter().map(|e| e.event_id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "2"]);
+        assert_eq!(fetcher.inner.requests.lock().unwrap().len(), 1);
+
+        options.deadline = Some(Instant::now());
+        options.fail_on_deadline = true;
+        let fetcher = ScriptedFetcher::new(pages());
+        assert!(
+            fetch_events_recursive_with_fetcher(
+                &fetcher,
+                date(2025, 12, 1),
+                date(2025, 12, 31),
+                &options
+            )
+            .await
+            .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_count_events() {
+        let fetcher = ScriptedFetcher::new(vec![
+            Ok(page_xml(&[("1", "01/12/2025"), ("2", "05/12/2025")])),
+            Ok(page_xml(&[("2", "05/12/2025"), ("3", "31/12/2025")])),
+        ]);
+        let count = count_events_with_fetcher(&fetcher, date(2025, 12, 1), date(2025, 12, 31))
+            .await
+            .unwrap();
+        assert_eq!(count, 3);
+        assert_eq!(fetcher.requests.lock().unwrap().len(), 2);
+
+        let with_header = page_xml(&[("1", "01/12/2025")]).replacen(
+            "<SOCSCalendar>",
+            r#"<SOCSCalendar><Header StartDate="01/12/2025" EndDate="31/12/2025" TotalCount="40"/>"#,
+            1,
+        );
+        let enveloped = serde_json::json!({ "data": with_header }).to_string();
+        for page in [with_header, enveloped] {
+            let fetcher = ScriptedFetcher::new(vec![Ok(page)]);
+            let count = count_events_with_fetcher(&fetcher, date(2025, 12, 1), date(2025, 12, 31))
+                .await
+                .unwrap();
+            assert_eq!(count, 40);
+            assert_eq!(fetcher.requests.lock().unwrap().len(), 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_best_effort_salvages_earlier_pages() {
+        let fetcher = ScriptedFetcher::new(vec![
+            Ok(page_xml(&[("1", "01/12/2025"), ("2", "05/12/2025")])),
+            Ok(page_xml(&[("2", "05/12/2025"), ("3", "12/12/2025")])),
+            Err(anyhow::anyhow!("Connection reset")),
+            Err(anyhow::anyhow!("Connection reset")),
+            Err(anyhow::anyhow!("Connection reset")),
+        ]);
+
+        let (events, error) = best_effort_with_fetcher(
+            &fetcher,
+            date(2025, 12, 1),
+            date(2025, 12, 31),
+            Duration::ZERO,
+        )
+        .await;
+
+        let ids: Vec<&str> = events.iter().map(|e| e.event_id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "2", "3"]);
+        let error = error.unwrap();
+        assert_eq!(
+            (error.start, error.end),
+            (date(2025, 12, 12), date(2025, 12, 31))
+        );
+        assert!(error.to_string().contains("Connection reset"));
+        // Two good pages, then the last one tried three times
+        assert_eq!(fetcher.requests.lock().unwrap().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_max_events_stops_early() {
+        let fetcher = ScriptedFetcher::new(vec![
+            Ok(page_xml(&[
+                ("1", "01/12/2025"),
+                ("2", "02/12/2025"),
+                ("3", "05/12/2025"),
+            ])),
+            Ok(page_xml(&[("3", "05/12/2025"), ("4", "12/12/2025")])),
+            Ok(page_xml(&[("4", "12/12/2025"), ("5", "31/12/2025")])),
+        ]);
+        let options = FetchOptions {
+            max_events: Some(3),
+            ..Default::default()
+        };
+
+        let outcome = fetch_events_recursive_with_fetcher(
+            &fetcher,
+            date(2025, 12, 1),
+            date(2025, 12, 31),
+            &options,
+        )
+        .await
+        .unwrap();
+
+        let ids: Vec<&str> = outcome.events.iter().map(|e| e.event_id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "2", "3"]);
+        assert_eq!(fetcher.requests.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_inclusive_end_pads_request_and_clips() {
+        let fetcher = ScriptedFetcher::new(vec![Ok(page_xml(&[
+            ("1", "01/12/2025"),
+            ("2", "10/12/2025"),
+            ("3", "11/12/2025"),
+        ]))]);
+        let options = FetchOptions {
+            end_inclusivity: EndInclusivity::Inclusive,
+            ..Default::default()
+        };
+
+        let outcome = fetch_events_recursive_with_fetcher(
+            &fetcher,
+            date(2025, 12, 1),
+            date(2025, 12, 10),
+            &options,
+        )
+        .await
+        .unwrap();
+
+        let ids: Vec<&str> = outcome.events.iter().map(|e| e.event_id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "2"]);
+        assert_eq!(
+            fetcher.requests.lock().unwrap()[0],
+            (date(2025, 12, 1), date(2025, 12, 11))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_max_span_days_splits_long_ranges() {
+        let fetcher = ScriptedFetcher::new(vec![
+            Ok(page_xml(&[("1", "01/01/2024"), ("2", "31/12/2024")])),
+            Ok(page_xml(&[("3", "01/06/2025"), ("4", "01/01/2026")])),
+            Ok(page_xml(&[("5", "31/12/2026")])),
+        ]);
+        let options = FetchOptions {
+            max_span_days: Some(366),
+            ..Default::default()
+        };
+
+        let outcome = fetch_events_recursive_with_fetcher(
+            &fetcher,
+            date(2024, 1, 1),
+            date(2026, 12, 31),
+            &options,
+        )
+        .await
+        .unwrap();
+
+        let ids: Vec<&str> = outcome.events.iter().map(|e| e.event_id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "2", "3", "4", "5"]);
+        assert_eq!(
+            *fetcher.requests.lock().unwrap(),
+            vec![
+                (date(2024, 1, 1), date(2024, 12, 31)),
+                (date(2025, 1, 1), date(2026, 1, 1)),
+                (date(2026, 1, 2), date(2026, 12, 31)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_skip_failed_page() {
+        let server = mock_server(vec![
+            page(&[("1", "01/12/2025"), ("2", "05/12/2025")]),
+            error_response(500),
+            page(&[("3", "20/12/2025"), ("4", "31/12/2025")]),
+        ]);
+        let options = FetchOptions {
+            skip_failed_pages: true,
+            ..Default::default()
+        };
+
+        let outcome = fetch_events_recursive_with_options(
+            &server.url,
+            date(2025, 12, 1),
+            date(2025, 12, 31),
+            &options,
+        )
+        .await
+        .unwrap();
+
+        let ids: Vec<&str> = outcome.events.iter().map(|e| e.event_id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "2", "3", "4"]);
+        assert_eq!(outcome.skipped.len(), 1);
+        assert_eq!(outcome.skipped[0].start, date(2025, 12, 5));
+        assert_eq!(outcome.skipped[0].end, date(2025, 12, 11));
+    }
+
+    #[tokio::test]
+    async fn test_stream_dedups_overlapping_pages() {
+        let server = mock_server(vec![
+            page(&[("1", "01/12/2025"), ("2", "05/12/2025")]),
+            page(&[("2", "05/12/2025"), ("3", "31/12/2025")]),
+        ]);
+
+        let events: Vec<CalendarEvent> =
+            fetch_events_recursive_stream(&server.url, date(2025, 12, 1), date(2025, 12, 31))
+                .map(|event| event.unwrap())
+                .collect()
+                .await;
+
+        let ids: Vec<&str> = events.iter().map(|e| e.event_id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "2", "3"]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_events_ranges_shared_boundary() {
+        let server = mock_server(vec![
+            page(&[("1", "01/12/2025"), ("2", "10/12/2025")]),
+            page(&[("2", "10/12/2025"), ("3", "20/12/2025")]),
+        ]);
+        let ranges = [
+            (date(2025, 12, 1), date(2025, 12, 10)),
+            (date(2025, 12, 10), date(2025, 12, 20)),
+        ];
+
+        let events = fetch_events_ranges(&server.url, &ranges).await.unwrap();
+        let ids: Vec<&str> = events.iter().map(|e| e.event_id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "2", "3"]);
+    }
+
+    #[test]
+    fn test_dedup_survivor_is_order_independent() {
+        let day = date(2025, 12, 10);
+        let mut original = all_day("1", day);
+        original.title = "Carol Service".to_string();
+        let mut updated = original.clone();
+        updated.location = "Chapel".to_string();
+        let other = all_day("2", day);
+
+        let mut forwards = vec![original.clone(), other.clone(), updated.clone()];
+        let mut backwards = vec![updated, other, original];
+        dedup_and_sort(&mut forwards);
+        dedup_and_sort(&mut backwards);
+
+        assert_eq!(forwards, backwards);
+        assert_eq!(forwards.len(), 2);
+        assert_eq!(forwards[0].location, "");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_by_terms() {
+        let server = mock_server(vec![
+            page(&[("1", "01/12/2025"), ("2", "10/12/2025")]),
+            page(&[("2", "10/12/2025"), ("3", "20/12/2025")]),
+        ]);
+        let terms = vec![
+            (
+                "Michaelmas".to_string(),
+                date(2025, 12, 1),
+                date(2025, 12, 10),
+            ),
+            ("Lent".to_string(), date(2025, 12, 11), date(2025, 12, 20)),
+        ];
+
+        let by_term = fetch_by_terms(&server.url, &terms).await.unwrap();
+        let ids = |term: &str| -> Vec<&str> {
+            by_term[term].iter().map(|e| e.event_id.as_str()).collect()
+        };
+        assert_eq!(ids("Michaelmas"), vec!["1", "2"]);
+        assert_eq!(ids("Lent"), vec!["3"]);
+    }
+
+    #[test]
+    fn test_preview_urls() {
+        let base = "https://www.socscms.com/socs/xml/SOCScalendar.ashx?ID=42&key=abc";
+        let urls = preview_urls(
+            base,
+            date(2025, 12, 1),
+            date(2025, 12, 20),
+            &FetchOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(urls.len(), 3);
+        assert_eq!(
+            urls[0],
+            "https://www.socscms.com/socs/xml/SOCScalendar.ashx?ID=42&key=abc\
+             &startdate=01%20Dec%2025&enddate=20%20Dec%2025\
+             &Sport=0&CoCurricular=0&IncludeInternal=1&IncludeUnpublished=1"
+        );
+        assert!(urls[2].contains("startdate=15%20Dec%2025"));
+        assert!(
+            preview_urls(
+                "not a url",
+                date(2025, 12, 1),
+                date(2025, 12, 20),
+                &FetchOptions::default()
+            )
+            .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_watch_reports_changes_only() {
+        let before = page(&[("1", "10/12/2025"), ("2", "31/12/2025")]);
+        let after = page(&[("1", "10/12/2025"), ("3", "31/12/2025")]);
+        let server = mock_server(vec![before.clone(), before, after.clone(), after]);
+
+        let mut diffs = Vec::new();
+        let result = watch(
+            &server.url,
+            date(2025, 12, 1),
+            date(2025, 12, 31),
+            Duration::ZERO,
+            |diff| diffs.push(diff),
+        )
+        .await;
+
+        // The watch ends once the mock server has no responses left
+        assert!(result.is_err());
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].added[0].event_id, "3");
+        assert_eq!(diffs[0].removed[0].event_id, "2");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_events_multi_tagged() {
+        let north = mock_server(vec![page(&[("1", "02/12/2025"), ("2", "31/12/2025")])]);
+        let south = mock_server(vec![page(&[("1", "01/12/2025"), ("1", "01/12/2025")])]);
+        let sources = vec![
+            ("North".to_string(), north.url.clone()),
+            ("South".to_string(), south.url.clone()),
+        ];
+
+        let events = fetch_events_multi_tagged(&sources, date(2025, 12, 1), date(2025, 12, 31))
+            .await
+            .unwrap();
+
+        let tagged: Vec<(&str, &str)> = events
+            .iter()
+            .map(|e| (e.source.as_str(), e.event.event_id.as_str()))
+            .collect();
+        assert_eq!(tagged, vec![("South", "1"), ("North", "1"), ("North", "2")]);
+    }
+
+    #[test]
+    fn test_window_end_is_inclusive() {
+        assert_eq!(
+            window_end(date(2025, 12, 10), 0).unwrap(),
+            date(2025, 12, 10)
+        );
+        assert_eq!(window_end(date(2025, 12, 28), 7).unwrap(), date(2026, 1, 4));
+    }
+
+    #[tokio::test]
+    async fn test_max_response_bytes() {
+        let server = mock_server(vec![page(&[("1", "01/12/2025")])]);
+        let options = FetchOptions {
+            max_response_bytes: Some(16),
+            ..Default::default()
+        };
+
+        let error = fetch_events_recursive_with_options(
+            &server.url,
+            date(2025, 12, 1),
+            date(2025, 12, 31),
+            &options,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<ResponseTooLarge>(),
+            Some(&ResponseTooLarge { limit: 16 })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_page_errors_by_default() {
+        let server = mock_server(vec![
+            page(&[("1", "01/12/2025"), ("2", "05/12/2025")]),
+            error_response(500),
+        ]);
+
+        let result =
+            fetch_events_recursive(&server.url, date(2025, 12, 1), date(2025, 12, 31)).await;
+        assert!(result.is_err());
+    }
+}
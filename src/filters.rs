@@ -0,0 +1,552 @@
+use crate::models::{CalendarEvent, EventStatus, EventTime};
+use chrono::{Duration, NaiveDateTime, NaiveTime};
+use std::collections::{HashMap, HashSet, VecDeque};
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+/// Keep only events that are still in progress or yet to start at `from`
+///
+/// An event is dropped once its end is before `from`. All-day events last for
+/// their whole end date, so an all-day event on `from`'s date is kept.
+pub fn upcoming(events: Vec<CalendarEvent>, from: NaiveDateTime) -> Vec<CalendarEvent> {
+    events
+        .into_iter()
+        .filter(|event| !has_ended(event, from))
+        .collect()
+}
+
+/// Remove events marked [`EventStatus::Cancelled`], keeping the rest in order
+pub fn drop_cancelled(events: Vec<CalendarEvent>) -> Vec<CalendarEvent> {
+    events
+        .into_iter()
+        .filter(|event| event.status != EventStatus::Cancelled)
+        .collect()
+}
+
+/// Keep only all-day events, e.g. for a term overview banner
+pub fn all_day_only(events: Vec<CalendarEvent>) -> Vec<CalendarEvent> {
+    events
+        .into_iter()
+        .filter(|event| event.start.is_all_day())
+        .collect()
+}
+
+/// Keep timed events starting between `earliest` and `latest` inclusive
+///
+/// All-day events have no start time, so `include_all_day` decides whether
+/// they are kept.
+pub fn between_times(
+    events: &[CalendarEvent],
+    earliest: NaiveTime,
+    latest: NaiveTime,
+    include_all_day: bool,
+) -> Vec<CalendarEvent> {
+    events
+        .iter()
+        .filter(|event| match &event.start {
+            EventTime::AllDay(_) => include_all_day,
+            EventTime::Specific { time, .. } => (earliest..=latest).contains(time),
+        })
+        .cloned()
+        .collect()
+}
+
+/// Move every event's start and end by `by`
+///
+/// Timed events roll over midnight onto the next or previous day as needed.
+/// All-day events only move by the whole days in `by`, so a 90 minute shift
+/// leaves them where they are. Times that would fall outside chrono's range
+/// are left unchanged.
+pub fn shift_events(events: &mut [CalendarEvent], by: Duration) {
+    for event in events {
+        event.start = shift_time(&event.start, by);
+        event.end = shift_time(&event.end, by);
+    }
+}
+
+fn shift_time(event_time: &EventTime, by: Duration) -> EventTime {
+    match event_time {
+        EventTime::AllDay(date) => {
+            let days = Duration::days(by.num_days());
+            EventTime::AllDay(date.checked_add_signed(days).unwrap_or(*date))
+        }
+        EventTime::Specific { date, time } => {
+            let shifted = date.and_time(*time);
+            let shifted = shifted.checked_add_signed(by).unwrap_or(shifted);
+            EventTime::Specific {
+                date: shifted.date(),
+                time: shifted.time(),
+            }
+        }
+    }
+}
+
+/// Rewrite each event's location to its canonical name from `aliases`
+///
+/// Keys are alias spellings ("Mainhall", "MH") and values the canonical name
+/// ("Main Hall"). Lookups ignore case and surrounding whitespace; locations
+/// with no matching alias are left unchanged.
+pub fn normalize_locations(events: &mut [CalendarEvent], aliases: &HashMap<String, String>) {
+    let aliases: HashMap<String, &String> = aliases
+        .iter()
+        .map(|(alias, canonical)| (alias.trim().to_lowercase(), canonical))
+        .collect();
+    for event in events {
+        if let Some(canonical) = aliases.get(&event.location.trim().to_lowercase()) {
+            event.location = canonical.to_string();
+        }
+    }
+}
+
+/// Which way [`sort_events`] orders events in time
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Earliest first
+    #[default]
+    Ascending,
+    /// Most recent first
+    Descending,
+}
+
+/// Sort events by start, then end, in the given direction
+///
+/// Events with the same start and end are ordered by ID ascending either
+/// way, so the result is the same whatever order the events arrived in.
+pub fn sort_events(events: &mut [CalendarEvent], order: SortOrder) {
+    match order {
+        SortOrder::Ascending => events.sort_by(|a, b| a.chronological_cmp(b)),
+        SortOrder::Descending => events.sort_by(|a, b| {
+            b.start
+                .cmp(&a.start)
+                .then_with(|| b.end.cmp(&a.end))
+                .then_with(|| a.event_id.cmp(&b.event_id))
+        }),
+    }
+}
+
+/// Sort events chronologically into a queue ready to be drained from the front
+pub fn into_chronological_queue(mut events: Vec<CalendarEvent>) -> VecDeque<CalendarEvent> {
+    events.sort_by(|a, b| a.chronological_cmp(b));
+    events.into()
+}
+
+/// Remove events whose ID has already appeared, keeping the first occurrence
+/// and otherwise preserving order
+pub fn dedup_in_place(events: &mut Vec<CalendarEvent>) {
+    let mut seen = HashSet::new();
+    events.retain(|event| seen.insert(event.event_id.clone()));
+}
+
+/// Collapse events sharing an ID into one record holding the most complete details
+///
+/// The merged event sits where the ID first appeared. Its title, location
+/// and description are the longest non-blank values seen, its categories
+/// are the union in order of first appearance (ignoring case), and the
+/// latest `modified` and first known `priority`, `url`, `timezone` and
+/// `group` are kept. A cancellation in any copy makes the merged event
+/// cancelled; otherwise its status and times come from the first occurrence.
+pub fn dedup_merge_in_place(events: &mut Vec<CalendarEvent>) {
+    let mut first_index: HashMap<_, usize> = HashMap::new();
+    let mut merged: Vec<CalendarEvent> = Vec::with_capacity(events.len());
+    for event in events.drain(..) {
+        match first_index.get(&event.event_id) {
+            Some(&index) => merge_into(&mut merged[index], event),
+            None => {
+                first_index.insert(event.event_id.clone(), merged.len());
+                merged.push(event);
+            }
+        }
+    }
+    *events = merged;
+}
+
+fn merge_into(kept: &mut CalendarEvent, other: CalendarEvent) {
+    if other.title.trim().len() > kept.title.trim().len() {
+        kept.title = other.title;
+    }
+    if other.location.trim().len() > kept.location.trim().len() {
+        kept.location = other.location;
+    }
+    let description_len = |d: &Option<String>| d.as_deref().map_or(0, |d| d.trim().len());
+    if description_len(&other.description) > description_len(&kept.description) {
+        kept.description = other.description;
+    }
+    // Match the parser's sort_categories, which folds case with to_lowercase
+    let fold = |c: &str| c.trim().to_lowercase();
+    for category in other.categories {
+        if !kept.categories.iter().any(|c| fold(c) == fold(&category)) {
+            kept.categories.push(category);
+        }
+    }
+    kept.modified = kept.modified.max(other.modified);
+    kept.priority = kept.priority.or(other.priority);
+    kept.url = kept.url.take().or(other.url);
+    kept.timezone = kept.timezone.or(other.timezone);
+    kept.group = kept.group.take().or(other.group);
+    if other.status == EventStatus::Cancelled {
+        kept.status = EventStatus::Cancelled;
+    }
+}
+
+/// Overlay `updates` onto `base` by ID, e.g. to refresh a local cache
+///
+/// An event in `updates` replaces any event in `base` with the same ID, and
+/// events new in `updates` are added. Duplicates within `updates` are first
+/// combined with [`dedup_merge_in_place`]. The result is in chronological
+/// order.
+pub fn merge_events(base: Vec<CalendarEvent>, updates: Vec<CalendarEvent>) -> Vec<CalendarEvent> {
+    let mut updates = updates;
+    dedup_merge_in_place(&mut updates);
+    let updated: HashSet<_> = updates.iter().map(|e| e.event_id.clone()).collect();
+
+    let mut merged: Vec<CalendarEvent> = base
+        .into_iter()
+        .filter(|event| !updated.contains(&event.event_id))
+        .collect();
+    merged.extend(updates);
+    merged.sort_by(|a, b| a.chronological_cmp(b));
+    merged
+}
+
+/// Sort events by ID, numerically if every ID in the batch is a number
+///
+/// Lexical ordering would put "10" ahead of "9", so purely numeric batches are
+/// compared as integers. Mixed batches fall back to plain string ordering.
+pub fn sort_by_id(events: &mut [CalendarEvent]) {
+    if events.iter().all(|e| e.event_id.as_u64().is_some()) {
+        events.sort_by_key(|e| e.event_id.as_u64());
+    } else {
+        events.sort_by(|a, b| a.event_id.cmp(&b.event_id));
+    }
+}
+
+/// Find events whose title or description contains `query`
+///
+/// Matching ignores case and accents, so "cafe" finds "Café". An empty query
+/// matches every event.
+pub fn search<'a>(events: &'a [CalendarEvent], query: &str) -> Vec<&'a CalendarEvent> {
+    let query = fold(query);
+    events
+        .iter()
+        .filter(|event| {
+            fold(&event.title).contains(&query)
+                || event
+                    .description
+                    .as_deref()
+                    .is_some_and(|d| fold(d).contains(&query))
+        })
+        .collect()
+}
+
+/// Lowercase and strip combining accents for loose comparison
+fn fold(text: &str) -> String {
+    text.nfd()
+        .filter(|c| !is_combining_mark(*c))
+        .collect::<String>()
+        .to_lowercase()
+}
+
+fn has_ended(event: &CalendarEvent, from: NaiveDateTime) -> bool {
+    match &event.end {
+        EventTime::AllDay(date) => *date < from.date(),
+        EventTime::Specific { date, time } => date.and_time(*time) < from,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::EventId;
+    use crate::test_utils::{all_day, date, event, time, timed};
+
+    #[test]
+    fn test_upcoming() {
+        let day = date(2025, 12, 10);
+        let from = day.and_hms_opt(12, 0, 0).unwrap();
+
+        let events = vec![
+            event("past", timed(day, 8, 30), timed(day, 9, 30)),
+            event("current", timed(day, 11, 0), timed(day, 13, 0)),
+            event("future", timed(day, 14, 0), timed(day, 15, 0)),
+            all_day("today", day),
+            all_day("yesterday", date(2025, 12, 9)),
+        ];
+
+        let ids: Vec<EventId> = upcoming(events, from)
+            .into_iter()
+            .map(|e| e.event_id)
+            .collect();
+        assert_eq!(ids, vec!["current", "future", "today"]);
+    }
+
+    #[test]
+    fn test_into_chronological_queue() {
+        let events = vec![
+            all_day("thursday", date(2025, 12, 11)),
+            event(
+                "wednesday-pm",
+                timed(date(2025, 12, 10), 14, 0),
+                timed(date(2025, 12, 10), 15, 0),
+            ),
+            all_day("wednesday", date(2025, 12, 10)),
+            event(
+                "wednesday-am",
+                timed(date(2025, 12, 10), 8, 30),
+                timed(date(2025, 12, 10), 9, 0),
+            ),
+        ];
+
+        let mut queue = into_chronological_queue(events);
+        let mut order = Vec::new();
+        while let Some(event) = queue.pop_front() {
+            order.push(event.event_id);
+        }
+        assert_eq!(
+            order,
+            vec!["wednesday", "wednesday-am", "wednesday-pm", "thursday"]
+        );
+    }
+
+    #[test]
+    fn test_sort_by_id_numeric() {
+        let day = date(2025, 12, 10);
+        let mut events = vec![all_day("10", day), all_day("9", day), all_day("100", day)];
+        sort_by_id(&mut events);
+        let ids: Vec<EventId> = events.into_iter().map(|e| e.event_id).collect();
+        assert_eq!(ids, vec!["9", "10", "100"]);
+    }
+
+    #[test]
+    fn test_sort_by_id_mixed_falls_back_to_lexical() {
+        let day = date(2025, 12, 10);
+        let mut events = vec![all_day("9", day), all_day("10", day), all_day("A1", day)];
+        sort_by_id(&mut events);
+        let ids: Vec<EventId> = events.into_iter().map(|e| e.event_id).collect();
+        assert_eq!(ids, vec!["10", "9", "A1"]);
+    }
+
+    #[test]
+    fn test_merge_events() {
+        let day = date(2025, 12, 10);
+        let base = vec![
+            event("1", timed(day, 9, 0), timed(day, 10, 0)),
+            event("2", timed(day, 11, 0), timed(day, 12, 0)),
+        ];
+        let mut moved = base[1].clone();
+        moved.start = timed(day, 8, 0);
+        moved.end = timed(day, 8, 45);
+        let mut linked = moved.clone();
+        linked.url = Some("https://www.socscms.com/socs/event.aspx?id=2".to_string());
+        let updates = vec![moved, all_day("3", day), linked];
+
+        let merged = merge_events(base, updates);
+        let ids: Vec<&str> = merged.iter().map(|e| e.event_id.as_str()).collect();
+        assert_eq!(ids, vec!["3", "2", "1"]);
+        assert_eq!(merged[1].start, timed(day, 8, 0));
+        assert!(merged[1].url.is_some());
+    }
+
+    #[test]
+    fn test_drop_cancelled() {
+        let day = date(2025, 12, 10);
+        let mut fixture = all_day("2", day);
+        fixture.status = EventStatus::Cancelled;
+        let mut trial = all_day("3", day);
+        trial.status = EventStatus::Tentative;
+
+        let kept = drop_cancelled(vec![all_day("1", day), fixture, trial]);
+        let ids: Vec<&str> = kept.iter().map(|e| e.event_id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "3"]);
+    }
+
+    #[test]
+    fn test_all_day_only() {
+        let day = date(2025, 12, 10);
+        let events = vec![
+            all_day("inset", day),
+            event("chapel", timed(day, 8, 30), timed(day, 9, 0)),
+            all_day("founders", date(2025, 12, 12)),
+        ];
+
+        let ids: Vec<EventId> = all_day_only(events)
+            .into_iter()
+            .map(|e| e.event_id)
+            .collect();
+        assert_eq!(ids, vec!["inset", "founders"]);
+    }
+
+    #[test]
+    fn test_dedup_in_place_keeps_first() {
+        let day = date(2025, 12, 10);
+        let mut first = all_day("1", day);
+        first.title = "First".to_string();
+        let mut events = vec![first, all_day("2", day), all_day("1", day)];
+
+        dedup_in_place(&mut events);
+        let ids: Vec<EventId> = events.iter().map(|e| e.event_id.clone()).collect();
+        assert_eq!(ids, vec!["1", "2"]);
+        assert_eq!(events[0].title, "First");
+    }
+
+    #[test]
+    fn test_sort_events_descending() {
+        let day = date(2025, 12, 10);
+        let mut events = vec![
+            event("1", timed(day, 9, 0), timed(day, 10, 0)),
+            all_day("2", date(2025, 12, 12)),
+            event("4", timed(day, 14, 0), timed(day, 15, 0)),
+            event("3", timed(day, 14, 0), timed(day, 15, 0)),
+        ];
+
+        sort_events(&mut events, SortOrder::Descending);
+        let ids: Vec<&str> = events.iter().map(|e| e.event_id.as_str()).collect();
+        assert_eq!(ids, vec!["2", "3", "4", "1"]);
+
+        sort_events(&mut events, SortOrder::Ascending);
+        let ids: Vec<&str> = events.iter().map(|e| e.event_id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "3", "4", "2"]);
+    }
+
+    #[test]
+    fn test_normalize_locations() {
+        let day = date(2025, 12, 10);
+        let aliases: HashMap<String, String> = ["Main Hall", "Mainhall", "MH"]
+            .into_iter()
+            .map(|alias| (alias.to_string(), "Main Hall".to_string()))
+            .collect();
+        let mut events: Vec<CalendarEvent> = ["main hall", "MAINHALL", " mh ", "Chapel"]
+            .into_iter()
+            .enumerate()
+            .map(|(i, location)| {
+                let mut event = all_day(&i.to_string(), day);
+                event.location = location.to_string();
+                event
+            })
+            .collect();
+
+        normalize_locations(&mut events, &aliases);
+        let locations: Vec<&str> = events.iter().map(|e| e.location.as_str()).collect();
+        assert_eq!(
+            locations,
+            vec!["Main Hall", "Main Hall", "Main Hall", "Chapel"]
+        );
+    }
+
+    #[test]
+    fn test_dedup_merge_keeps_most_complete() {
+        let day = date(2025, 12, 10);
+        let mut sparse = all_day("1", day);
+        sparse.description = Some("Coaches leave at 7am".to_string());
+        sparse.categories = vec!["Sport".to_string(), "Café".to_string()];
+        let mut other = all_day("1", day);
+        other.location = "Eton College".to_string();
+        other.categories = vec!["sport".to_string(), " CAFÉ".to_string(), "Away".to_string()];
+        other.url = Some("https://www.socscms.com/socs/event.aspx?id=1".to_string());
+        other.group = Some("Wellington".to_string());
+        other.timezone = Some(chrono_tz::Europe::London);
+        other.status = EventStatus::Cancelled;
+        let mut events = vec![sparse, all_day("2", day), other];
+
+        dedup_merge_in_place(&mut events);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event_id, "1");
+        assert_eq!(
+            events[0].description.as_deref(),
+            Some("Coaches leave at 7am")
+        );
+        assert_eq!(events[0].location, "Eton College");
+        assert_eq!(events[0].categories, vec!["Sport", "Café", "Away"]);
+        assert_eq!(
+            events[0].url.as_deref(),
+            Some("https://www.socscms.com/socs/event.aspx?id=1")
+        );
+        assert_eq!(events[0].group.as_deref(), Some("Wellington"));
+        assert_eq!(events[0].timezone, Some(chrono_tz::Europe::London));
+        assert_eq!(events[0].status, EventStatus::Cancelled);
+        assert_eq!(events[1].event_id, "2");
+    }
+
+    #[test]
+    fn test_between_times_boundaries() {
+        let day = date(2025, 12, 10);
+        let events = vec![
+            event("early", timed(day, 7, 59), timed(day, 8, 30)),
+            event("opening", timed(day, 8, 0), timed(day, 9, 0)),
+            event("closing", timed(day, 12, 0), timed(day, 13, 0)),
+            event("late", timed(day, 12, 1), timed(day, 13, 0)),
+            all_day("inset", day),
+        ];
+
+        let ids: Vec<EventId> = between_times(&events, time(8, 0), time(12, 0), false)
+            .into_iter()
+            .map(|e| e.event_id)
+            .collect();
+        assert_eq!(ids, vec!["opening", "closing"]);
+
+        let ids: Vec<EventId> = between_times(&events, time(8, 0), time(12, 0), true)
+            .into_iter()
+            .map(|e| e.event_id)
+            .collect();
+        assert_eq!(ids, vec!["opening", "closing", "inset"]);
+    }
+
+    #[test]
+    fn test_shift_events_crossing_midnight() {
+        let day = date(2025, 12, 10);
+        let mut events = vec![
+            event("late", timed(day, 23, 0), timed(day, 23, 30)),
+            all_day("inset", day),
+        ];
+
+        shift_events(&mut events, Duration::minutes(90));
+        assert_eq!(events[0].start, timed(date(2025, 12, 11), 0, 30));
+        assert_eq!(events[0].end, timed(date(2025, 12, 11), 1, 0));
+        assert_eq!(events[1].start, EventTime::AllDay(day));
+    }
+
+    #[test]
+    fn test_shift_events_whole_days_all_day() {
+        let mut events = vec![all_day("inset", date(2025, 12, 31))];
+
+        shift_events(&mut events, Duration::days(2));
+        assert_eq!(events[0].start, EventTime::AllDay(date(2026, 1, 2)));
+        assert_eq!(events[0].end, EventTime::AllDay(date(2026, 1, 2)));
+    }
+
+    #[test]
+    fn test_search_ignores_case_and_accents() {
+        let day = date(2025, 12, 10);
+        let mut cafe = all_day("1", day);
+        cafe.title = "Sixth Form Café".to_string();
+        let mut concert = all_day("2", day);
+        concert.title = "CHRISTMAS CONCERT".to_string();
+        let events = vec![cafe, concert];
+
+        let ids: Vec<&EventId> = search(&events, "cafe")
+            .iter()
+            .map(|e| &e.event_id)
+            .collect();
+        assert_eq!(ids, vec!["1"]);
+        let ids: Vec<&EventId> = search(&events, "Concert")
+            .iter()
+            .map(|e| &e.event_id)
+            .collect();
+        assert_eq!(ids, vec!["2"]);
+        let ids: Vec<&EventId> = search(&events, "CAFÉ")
+            .iter()
+            .map(|e| &e.event_id)
+            .collect();
+        assert_eq!(ids, vec!["1"]);
+    }
+
+    #[test]
+    fn test_search_matches_description() {
+        let day = date(2025, 12, 10);
+        let mut trip = all_day("1", day);
+        trip.description = Some("Coaches leave for the Musée d'Orsay at 7am".to_string());
+        let events = vec![trip, all_day("2", day)];
+
+        assert_eq!(search(&events, "musee").len(), 1);
+        assert!(search(&events, "louvre").is_empty());
+    }
+}
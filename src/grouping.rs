@@ -0,0 +1,472 @@
+use crate::models::{CalendarEvent, DayPart, EventTime};
+use chrono::{Datelike, Days, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Timelike, Weekday};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+/// Group events by the date they start on, in date order
+pub fn group_by_day(events: &[CalendarEvent]) -> BTreeMap<NaiveDate, Vec<CalendarEvent>> {
+    let mut days: BTreeMap<NaiveDate, Vec<CalendarEvent>> = BTreeMap::new();
+    for event in events {
+        days.entry(event.start.date())
+            .or_default()
+            .push(event.clone());
+    }
+    days
+}
+
+/// Group events by the week they start in, keyed by the week's first day
+///
+/// Weeks begin on `week_start`, so with `Weekday::Mon` an event on a Sunday
+/// is filed under the Monday six days before it.
+pub fn group_by_week(
+    events: &[CalendarEvent],
+    week_start: Weekday,
+) -> BTreeMap<NaiveDate, Vec<CalendarEvent>> {
+    let mut weeks: BTreeMap<NaiveDate, Vec<CalendarEvent>> = BTreeMap::new();
+    for event in events {
+        let week = event.start.date().week(week_start).first_day();
+        weeks.entry(week).or_default().push(event.clone());
+    }
+    weeks
+}
+
+/// Group events by their house or team, for house-specific calendar views
+///
+/// Events without a [`group`](CalendarEvent::group) are left out. Within a
+/// house, events keep their input order.
+pub fn group_by_house(events: &[CalendarEvent]) -> BTreeMap<String, Vec<CalendarEvent>> {
+    let mut houses: BTreeMap<String, Vec<CalendarEvent>> = BTreeMap::new();
+    for event in events {
+        if let Some(house) = &event.group {
+            houses.entry(house.clone()).or_default().push(event.clone());
+        }
+    }
+    houses
+}
+
+/// Group timed events by the part of the day they start in
+///
+/// Uses [`CalendarEvent::day_part`], so all-day events are left out. Parts
+/// with no events are omitted.
+pub fn group_by_day_part(events: &[CalendarEvent]) -> BTreeMap<DayPart, Vec<CalendarEvent>> {
+    let mut parts: BTreeMap<DayPart, Vec<CalendarEvent>> = BTreeMap::new();
+    for event in events {
+        if let Some(part) = event.day_part() {
+            parts.entry(part).or_default().push(event.clone());
+        }
+    }
+    parts
+}
+
+/// Split events into pages of `days_per_page` consecutive days for agenda views
+///
+/// Pages run back to back from the earliest event's start date until the last
+/// event is covered, and each is returned with its first date. Within a page,
+/// days without events are omitted from the map, while a page whose days are
+/// all empty is still returned (with an empty map) so page numbering stays
+/// tied to dates. A `days_per_page` of zero is treated as one.
+pub fn agenda_pages(
+    events: &[CalendarEvent],
+    days_per_page: usize,
+) -> Vec<(NaiveDate, BTreeMap<NaiveDate, Vec<CalendarEvent>>)> {
+    let days = group_by_day(events);
+    let (Some(&first), Some(&last)) = (days.keys().next(), days.keys().next_back()) else {
+        return Vec::new();
+    };
+    let page_len = Days::new(days_per_page.max(1) as u64);
+
+    let mut pages = Vec::new();
+    let mut page_start = first;
+    while page_start <= last {
+        let Some(next_start) = page_start.checked_add_days(page_len) else {
+            pages.push((
+                page_start,
+                days.range(page_start..).map(clone_day).collect(),
+            ));
+            break;
+        };
+        let page = days.range(page_start..next_start).map(clone_day).collect();
+        pages.push((page_start, page));
+        page_start = next_start;
+    }
+    pages
+}
+
+/// The date with the most events starting on it, and how many
+///
+/// Ties go to the earliest date. Returns `None` when there are no events.
+pub fn busiest_day(events: &[CalendarEvent]) -> Option<(NaiveDate, usize)> {
+    let mut counts: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+    for event in events {
+        *counts.entry(event.start.date()).or_default() += 1;
+    }
+    // max_by_key keeps the last maximum, so walk the dates newest first
+    counts.into_iter().rev().max_by_key(|&(_, count)| count)
+}
+
+/// Count timed events by the hour of day they start in, for heatmaps
+///
+/// Bucket 0 covers 00:00-00:59 and bucket 23 covers 23:00-23:59. All-day
+/// events have no start hour and aren't counted. See
+/// [`hour_histogram_spread`] to count an event in every hour it runs through.
+pub fn hour_histogram(events: &[CalendarEvent]) -> [usize; 24] {
+    let mut buckets = [0; 24];
+    for event in events {
+        if let EventTime::Specific { time, .. } = &event.start {
+            buckets[time.hour() as usize] += 1;
+        }
+    }
+    buckets
+}
+
+/// Count timed events in every hour of day they are running during
+///
+/// An event from 09:30 to 11:00 counts towards the 9 and 10 buckets; one
+/// that ends exactly on the hour doesn't count towards that hour, and one
+/// with no length counts towards its start hour. Events lasting a day or
+/// more count at most once per bucket. All-day events aren't counted.
+pub fn hour_histogram_spread(events: &[CalendarEvent]) -> [usize; 24] {
+    let mut buckets = [0; 24];
+    for event in events {
+        if event.start.is_all_day() {
+            continue;
+        }
+        let (start, end) = event.local_span();
+        let mut hours = [false; 24];
+        let mut hour = start
+            .with_minute(0)
+            .and_then(|t| t.with_second(0))
+            .unwrap_or(start);
+        loop {
+            hours[hour.hour() as usize] = true;
+            hour += TimeDelta::hours(1);
+            if hour >= end || hours.iter().all(|&h| h) {
+                break;
+            }
+        }
+        for (bucket, counted) in buckets.iter_mut().zip(hours) {
+            *bucket += counted as usize;
+        }
+    }
+    buckets
+}
+
+/// Total scheduled time in each category, e.g. for sport versus academic reports
+///
+/// Each event's [`duration`](CalendarEvent::duration) counts towards every
+/// category it belongs to, so all-day events count 24 hours per day. See
+/// [`category_durations_with`] to count them as a shorter school day.
+/// Events without categories aren't counted.
+pub fn category_durations(events: &[CalendarEvent]) -> BTreeMap<String, TimeDelta> {
+    category_durations_with(events, TimeDelta::days(1))
+}
+
+/// Total scheduled time in each category, counting `full_day` for each day of an all-day event
+pub fn category_durations_with(
+    events: &[CalendarEvent],
+    full_day: TimeDelta,
+) -> BTreeMap<String, TimeDelta> {
+    let mut totals: BTreeMap<String, TimeDelta> = BTreeMap::new();
+    for event in events {
+        let duration = if event.start.is_all_day() {
+            full_day * event.duration().num_days() as i32
+        } else {
+            event.duration()
+        };
+        for category in &event.categories {
+            *totals.entry(category.clone()).or_default() += duration;
+        }
+    }
+    totals
+}
+
+/// Collapse each day's events in `category` into one block from the earliest start to the latest end
+///
+/// Categories match case-insensitively and events are keyed on their start
+/// date, with any gaps between them ignored. All-day events cover their
+/// whole day, ending at the following midnight. Days are returned in order.
+pub fn coalesce_by_category(
+    events: &[CalendarEvent],
+    category: &str,
+) -> Vec<(NaiveDate, NaiveDateTime, NaiveDateTime)> {
+    let category = category.trim();
+    let mut days: BTreeMap<NaiveDate, (NaiveDateTime, NaiveDateTime)> = BTreeMap::new();
+    for event in events {
+        if !event
+            .categories
+            .iter()
+            .any(|c| c.trim().eq_ignore_ascii_case(category))
+        {
+            continue;
+        }
+        let (start, end) = event.local_span();
+        days.entry(event.start.date())
+            .and_modify(|(first, last)| {
+                *first = (*first).min(start);
+                *last = (*last).max(end);
+            })
+            .or_insert((start, end));
+    }
+    days.into_iter()
+        .map(|(date, (start, end))| (date, start, end))
+        .collect()
+}
+
+/// Events with the same title seen repeatedly on the same weekday and start time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeeklyPattern {
+    pub title: String,
+    pub weekday: Weekday,
+    /// The shared start time, or `None` for all-day events
+    pub time: Option<NaiveTime>,
+    pub occurrences: usize,
+}
+
+/// Infer regular weekly events, such as a Monday 08:30 assembly, from what was observed
+///
+/// Events are grouped by title (ignoring surrounding whitespace), weekday and
+/// start time. Only groups seen at least twice are returned, most frequent
+/// first, then by weekday, time and title.
+pub fn detect_weekly_patterns(events: &[CalendarEvent]) -> Vec<WeeklyPattern> {
+    let mut counts: BTreeMap<(u32, Option<NaiveTime>, &str), usize> = BTreeMap::new();
+    for event in events {
+        let time = match &event.start {
+            EventTime::AllDay(_) => None,
+            EventTime::Specific { time, .. } => Some(*time),
+        };
+        let weekday = event.start.date().weekday().num_days_from_monday();
+        *counts
+            .entry((weekday, time, event.title.trim()))
+            .or_default() += 1;
+    }
+
+    let mut patterns: Vec<WeeklyPattern> = counts
+        .into_iter()
+        .filter(|&(_, occurrences)| occurrences >= 2)
+        .map(|((weekday, time, title), occurrences)| WeeklyPattern {
+            title: title.to_string(),
+            weekday: Weekday::try_from(weekday as u8).unwrap_or(Weekday::Mon),
+            time,
+            occurrences,
+        })
+        .collect();
+    // The sort is stable, so ties keep the map's weekday/time/title order
+    patterns.sort_by_key(|pattern| Reverse(pattern.occurrences));
+    patterns
+}
+
+fn clone_day((date, events): (&NaiveDate, &Vec<CalendarEvent>)) -> (NaiveDate, Vec<CalendarEvent>) {
+    (*date, events.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{all_day, date, event, time, timed};
+
+    #[test]
+    fn test_category_durations() {
+        let day = date(2025, 12, 10);
+        let mut rugby = event("1", timed(day, 14, 0), timed(day, 16, 0));
+        rugby.categories = vec!["Sport".to_string(), "Whole School".to_string()];
+        let mut maths = event("2", timed(day, 9, 0), timed(day, 9, 45));
+        maths.categories = vec!["Academic".to_string()];
+        let mut sports_day = event(
+            "3",
+            EventTime::AllDay(day),
+            EventTime::AllDay(date(2025, 12, 11)),
+        );
+        sports_day.categories = vec!["Sport".to_string()];
+        let events = vec![rugby, maths, sports_day, all_day("4", day)];
+
+        let totals = category_durations(&events);
+        assert_eq!(totals.len(), 3);
+        assert_eq!(totals["Sport"], TimeDelta::hours(50));
+        assert_eq!(totals["Whole School"], TimeDelta::hours(2));
+        assert_eq!(totals["Academic"], TimeDelta::minutes(45));
+
+        let school_days = category_durations_with(&events, TimeDelta::hours(7));
+        assert_eq!(school_days["Sport"], TimeDelta::hours(16));
+    }
+
+    #[test]
+    fn test_group_by_day() {
+        let day = date(2025, 12, 10);
+        let events = vec![
+            event("1", timed(day, 8, 30), timed(day, 9, 0)),
+            all_day("2", date(2025, 12, 11)),
+            all_day("3", day),
+        ];
+
+        let days = group_by_day(&events);
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[&day].len(), 2);
+    }
+
+    #[test]
+    fn test_agenda_pages_two_weeks() {
+        let events = vec![
+            all_day("1", date(2025, 12, 1)),
+            all_day("2", date(2025, 12, 3)),
+            all_day("3", date(2025, 12, 8)),
+            all_day("4", date(2025, 12, 14)),
+        ];
+
+        let pages = agenda_pages(&events, 7);
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].0, date(2025, 12, 1));
+        assert_eq!(
+            pages[0].1.keys().copied().collect::<Vec<_>>(),
+            vec![date(2025, 12, 1), date(2025, 12, 3)]
+        );
+        assert_eq!(pages[1].0, date(2025, 12, 8));
+        assert_eq!(
+            pages[1].1.keys().copied().collect::<Vec<_>>(),
+            vec![date(2025, 12, 8), date(2025, 12, 14)]
+        );
+    }
+
+    #[test]
+    fn test_busiest_day() {
+        let day = date(2025, 12, 10);
+        let events = vec![
+            all_day("1", date(2025, 12, 9)),
+            event("2", timed(day, 8, 30), timed(day, 9, 0)),
+            event("3", timed(day, 14, 0), timed(day, 15, 0)),
+            all_day("4", day),
+        ];
+        assert_eq!(busiest_day(&events), Some((day, 3)));
+        assert_eq!(busiest_day(&[]), None);
+    }
+
+    #[test]
+    fn test_busiest_day_tie_goes_to_earliest() {
+        let events = vec![
+            all_day("1", date(2025, 12, 12)),
+            all_day("2", date(2025, 12, 12)),
+            all_day("3", date(2025, 12, 10)),
+            all_day("4", date(2025, 12, 10)),
+        ];
+        assert_eq!(busiest_day(&events), Some((date(2025, 12, 10), 2)));
+    }
+
+    #[test]
+    fn test_group_by_house() {
+        let day = date(2025, 12, 10);
+        let mut events: Vec<CalendarEvent> = ["1", "2", "3", "4"]
+            .into_iter()
+            .map(|id| all_day(id, day))
+            .collect();
+        events[0].group = Some("Wellington".to_string());
+        events[1].group = Some("Nelson".to_string());
+        events[2].group = Some("Wellington".to_string());
+
+        let houses = group_by_house(&events);
+        let ids = |house: &str| -> Vec<&str> {
+            houses[house].iter().map(|e| e.event_id.as_str()).collect()
+        };
+        assert_eq!(houses.len(), 2);
+        assert_eq!(ids("Wellington"), vec!["1", "3"]);
+        assert_eq!(ids("Nelson"), vec!["2"]);
+    }
+
+    #[test]
+    fn test_group_by_day_part() {
+        let day = date(2025, 12, 10);
+        let events = vec![
+            event("1", timed(day, 8, 30), timed(day, 9, 0)),
+            event("2", timed(day, 17, 0), timed(day, 18, 0)),
+            event("3", timed(day, 11, 0), timed(day, 12, 0)),
+            all_day("4", day),
+        ];
+
+        let parts = group_by_day_part(&events);
+        let ids: Vec<(DayPart, Vec<&str>)> = parts
+            .iter()
+            .map(|(part, events)| (*part, events.iter().map(|e| e.event_id.as_str()).collect()))
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                (DayPart::Morning, vec!["1", "3"]),
+                (DayPart::Evening, vec!["2"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hour_histograms() {
+        let day = date(2025, 12, 10);
+        let events = vec![
+            event("1", timed(day, 9, 0), timed(day, 9, 45)),
+            event("2", timed(day, 9, 30), timed(day, 11, 0)),
+            event("3", timed(day, 14, 0), timed(day, 16, 30)),
+            all_day("4", day),
+        ];
+
+        let mut expected = [0; 24];
+        expected[9] = 2;
+        expected[14] = 1;
+        assert_eq!(hour_histogram(&events), expected);
+
+        expected[10] = 1;
+        expected[15] = 1;
+        expected[16] = 1;
+        assert_eq!(hour_histogram_spread(&events), expected);
+    }
+
+    #[test]
+    fn test_coalesce_by_category() {
+        let day = date(2025, 12, 10);
+        let rugby = |id: &str, start: (u32, u32), end: (u32, u32)| {
+            let mut event = event(id, timed(day, start.0, start.1), timed(day, end.0, end.1));
+            event.categories = vec!["Rugby".to_string()];
+            event
+        };
+        let events = vec![
+            rugby("1", (14, 0), (15, 0)),
+            rugby("2", (9, 0), (10, 0)),
+            rugby("3", (16, 30), (17, 45)),
+            event("4", timed(day, 7, 0), timed(day, 18, 0)),
+        ];
+
+        assert_eq!(
+            coalesce_by_category(&events, "rugby"),
+            vec![(
+                day,
+                day.and_hms_opt(9, 0, 0).unwrap(),
+                day.and_hms_opt(17, 45, 0).unwrap()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_detect_weekly_patterns() {
+        let assembly = |id: &str, day: NaiveDate| {
+            let mut event = event(id, timed(day, 8, 30), timed(day, 9, 0));
+            event.title = "Assembly".to_string();
+            event
+        };
+        let mut events: Vec<CalendarEvent> = [1, 8, 15, 22]
+            .iter()
+            .map(|&day| assembly(&day.to_string(), date(2025, 12, day)))
+            .collect();
+        // Same title and time on a different weekday, and a one-off on a Monday
+        events.push(assembly("late", date(2025, 12, 2)));
+        events.push(event(
+            "concert",
+            timed(date(2025, 12, 1), 8, 30),
+            timed(date(2025, 12, 1), 9, 0),
+        ));
+
+        assert_eq!(
+            detect_weekly_patterns(&events),
+            vec![WeeklyPattern {
+                title: "Assembly".to_string(),
+                weekday: Weekday::Mon,
+                time: Some(time(8, 30)),
+                occurrences: 4,
+            }]
+        );
+    }
+}
@@ -0,0 +1,228 @@
+use crate::grouping::group_by_week;
+use crate::models::{CalendarEvent, EventStatus, EventTime, Priority};
+use chrono::{Days, NaiveDate, NaiveDateTime, Utc, Weekday};
+use std::collections::BTreeMap;
+
+/// Domain appended to event IDs so UIDs are globally unique
+const UID_DOMAIN: &str = "socs-calendar-parser";
+
+/// Render events as an iCalendar (RFC 5545) document
+///
+/// Timed events are written as floating local times and all-day events as
+/// `VALUE=DATE`, with the exclusive end date the format expects. `DTSTAMP` is
+/// the time the document was generated, in UTC.
+pub fn to_ics(events: &[CalendarEvent]) -> String {
+    to_ics_at(events, Utc::now().naive_utc())
+}
+
+/// Render one iCalendar document per week, keyed by the week's first day
+///
+/// Events are filed under the week they start in, with weeks beginning on
+/// `week_start`. Weeks without events are left out.
+pub fn to_ical_weekly(
+    events: &[CalendarEvent],
+    week_start: Weekday,
+) -> BTreeMap<NaiveDate, String> {
+    to_ical_weekly_at(events, week_start, Utc::now().naive_utc())
+}
+
+fn to_ical_weekly_at(
+    events: &[CalendarEvent],
+    week_start: Weekday,
+    now: NaiveDateTime,
+) -> BTreeMap<NaiveDate, String> {
+    group_by_week(events, week_start)
+        .into_iter()
+        .map(|(week, events)| (week, to_ics_at(&events, now)))
+        .collect()
+}
+
+fn to_ics_at(events: &[CalendarEvent], now: NaiveDateTime) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//socs-calendar-parser//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+    for event in events {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!(
+            "UID:{}@{}",
+            escape_text(event.event_id.as_str()),
+            UID_DOMAIN
+        ));
+        lines.push(format!("DTSTAMP:{}", now.format("%Y%m%dT%H%M%SZ")));
+        lines.push(format!("DTSTART{}", format_start(&event.start)));
+        lines.push(format!("DTEND{}", format_end(&event.end)));
+        lines.push(format!("SUMMARY:{}", escape_text(&event.title)));
+        if let Some(description) = &event.description {
+            lines.push(format!("DESCRIPTION:{}", escape_text(description)));
+        }
+        if !event.location.is_empty() {
+            lines.push(format!("LOCATION:{}", escape_text(&event.location)));
+        }
+        if let Some(priority) = event.priority {
+            // RFC 5545 ranks 1 as highest and 9 as lowest, with 5 in the middle
+            let rank = match priority {
+                Priority::High => 1,
+                Priority::Normal => 5,
+                Priority::Low => 9,
+            };
+            lines.push(format!("PRIORITY:{}", rank));
+        }
+        let status = match event.status {
+            EventStatus::Confirmed => "CONFIRMED",
+            EventStatus::Cancelled => "CANCELLED",
+            EventStatus::Tentative => "TENTATIVE",
+        };
+        lines.push(format!("STATUS:{}", status));
+        if let Some(url) = &event.url {
+            lines.push(format!("URL:{}", url));
+        }
+        if !event.categories.is_empty() {
+            let categories: Vec<String> = event.categories.iter().map(|c| escape_text(c)).collect();
+            lines.push(format!("CATEGORIES:{}", categories.join(",")));
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold_line(line) + "\r\n").collect()
+}
+
+fn format_start(time: &EventTime) -> String {
+    match time {
+        EventTime::AllDay(date) => format!(";VALUE=DATE:{}", date.format("%Y%m%d")),
+        EventTime::Specific { date, time } => {
+            format!(":{}", date.and_time(*time).format("%Y%m%dT%H%M%S"))
+        }
+    }
+}
+
+fn format_end(time: &EventTime) -> String {
+    match time {
+        // DTEND is exclusive, so an all-day event ends the following midnight
+        EventTime::AllDay(date) => {
+            let end = date.checked_add_days(Days::new(1)).unwrap_or(*date);
+            format!(";VALUE=DATE:{}", end.format("%Y%m%d"))
+        }
+        EventTime::Specific { .. } => format_start(time),
+    }
+}
+
+/// Escape a TEXT value: backslashes, semicolons, commas and newlines
+fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\r' => {
+                chars.next_if_eq(&'\n');
+                escaped.push_str("\\n");
+            }
+            '\n' => escaped.push_str("\\n"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Fold a content line so no physical line exceeds 75 octets
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut width = 0;
+    for c in line.chars() {
+        // Continuation lines start with a space, which counts towards the limit
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{all_day, date, event, timed};
+
+    fn now() -> NaiveDateTime {
+        date(2025, 12, 1).and_hms_opt(9, 0, 0).unwrap()
+    }
+
+    /// Undo folding and split into content lines
+    fn unfold(ics: &str) -> Vec<String> {
+        ics.replace("\r\n ", "")
+            .split("\r\n")
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_escapes_special_characters() {
+        let day = date(2025, 12, 10);
+        let mut chapel = event("12,3;4", timed(day, 8, 30), timed(day, 9, 0));
+        chapel.description = Some("Bring:\nhymn books, music\\notes; coats".to_string());
+
+        let lines = unfold(&to_ics_at(&[chapel], now()));
+        assert!(lines.contains(&"UID:12\\,3\\;4@socs-calendar-parser".to_string()));
+        assert!(
+            lines.contains(
+                &"DESCRIPTION:Bring:\\nhymn books\\, music\\\\notes\\; coats".to_string()
+            )
+        );
+        assert!(lines.contains(&"DTSTAMP:20251201T090000Z".to_string()));
+        assert!(lines.contains(&"DTSTART:20251210T083000".to_string()));
+
+        // Every content line is a NAME(;PARAM)*:VALUE pair with no raw newlines
+        for line in &lines {
+            assert!(line.contains(':'), "{line}");
+            assert!(!line.contains('\n'));
+        }
+    }
+
+    #[test]
+    fn test_all_day_end_is_exclusive() {
+        let lines = unfold(&to_ics_at(&[all_day("1", date(2025, 12, 31))], now()));
+        assert!(lines.contains(&"DTSTART;VALUE=DATE:20251231".to_string()));
+        assert!(lines.contains(&"DTEND;VALUE=DATE:20260101".to_string()));
+    }
+
+    #[test]
+    fn test_weekly_documents() {
+        let events = vec![
+            all_day("1", date(2025, 12, 8)),
+            all_day("2", date(2025, 12, 14)),
+            all_day("3", date(2025, 12, 15)),
+        ];
+
+        let weeks = to_ical_weekly_at(&events, Weekday::Mon, now());
+        assert_eq!(
+            weeks.keys().copied().collect::<Vec<_>>(),
+            vec![date(2025, 12, 8), date(2025, 12, 15)]
+        );
+        assert_eq!(weeks[&date(2025, 12, 8)], to_ics_at(&events[..2], now()));
+        let second = unfold(&weeks[&date(2025, 12, 15)]);
+        assert_eq!(second.first().map(String::as_str), Some("BEGIN:VCALENDAR"));
+        assert_eq!(second.last().map(String::as_str), Some("END:VCALENDAR"));
+        assert!(second.contains(&"UID:3@socs-calendar-parser".to_string()));
+        assert_eq!(second.iter().filter(|l| *l == "BEGIN:VEVENT").count(), 1);
+    }
+
+    #[test]
+    fn test_folds_long_lines() {
+        let day = date(2025, 12, 10);
+        let mut long = event("1", timed(day, 8, 30), timed(day, 9, 0));
+        long.title = "é".repeat(60);
+
+        let ics = to_ics_at(&[long], now());
+        assert!(ics.split("\r\n").all(|line| line.len() <= 75));
+        assert!(unfold(&ics).contains(&format!("SUMMARY:{}", "é".repeat(60))));
+    }
+}
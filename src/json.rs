@@ -0,0 +1,161 @@
+use crate::models::{CalendarEvent, EventId, EventStatus, EventTime, Priority, RawFields};
+use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+use chrono_tz::Tz;
+use serde::{Serialize, Serializer};
+
+/// Serializes a [`CalendarEvent`] with camelCase field names (`eventId` rather than `event_id`)
+///
+/// `CalendarEvent`'s own `Serialize` keeps snake_case names; wrap events in
+/// this for frontends that expect camelCase, e.g.
+/// `serde_json::to_string(&events.iter().map(CamelCase).collect::<Vec<_>>())`.
+#[derive(Debug, Clone, Copy)]
+pub struct CamelCase<'a>(pub &'a CalendarEvent);
+
+impl Serialize for CamelCase<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Event<'a> {
+            event_id: &'a EventId,
+            title: &'a str,
+            description: &'a Option<String>,
+            location: &'a str,
+            categories: &'a [String],
+            start: &'a EventTime,
+            end: &'a EventTime,
+            modified: &'a Option<NaiveDateTime>,
+            priority: &'a Option<Priority>,
+            url: &'a Option<String>,
+            status: EventStatus,
+            timezone: Option<Tz>,
+            group: &'a Option<String>,
+            raw: &'a Option<RawFields>,
+        }
+
+        let event = self.0;
+        Event {
+            event_id: &event.event_id,
+            title: &event.title,
+            description: &event.description,
+            location: &event.location,
+            categories: &event.categories,
+            start: &event.start,
+            end: &event.end,
+            modified: &event.modified,
+            priority: &event.priority,
+            url: &event.url,
+            status: event.status,
+            timezone: event.timezone,
+            group: &event.group,
+            raw: &event.raw,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Serialize events to JSON that is byte-for-byte the same for the same set of events
+///
+/// Events are ordered chronologically, with ties broken by ID and then by
+/// their remaining fields, and each event's categories are sorted. Fields
+/// are written in declaration order, so the output is safe to snapshot
+/// whatever order the events arrived in.
+pub fn to_json(events: &[CalendarEvent]) -> Result<String> {
+    to_json_with_options(events, &JsonOptions::default())
+}
+
+/// Options controlling what [`to_json_with_options`] writes
+#[derive(Debug, Clone, Default)]
+pub struct JsonOptions {
+    /// Add an `interval` field to each event holding
+    /// [`CalendarEvent::iso_interval`]
+    pub include_interval: bool,
+}
+
+/// Serialize events like [`to_json`], with optional extra fields
+pub fn to_json_with_options(events: &[CalendarEvent], options: &JsonOptions) -> Result<String> {
+    #[derive(Serialize)]
+    struct WithInterval<'a> {
+        #[serde(flatten)]
+        event: &'a CalendarEvent,
+        interval: String,
+    }
+
+    let mut events: Vec<CalendarEvent> = events.to_vec();
+    for event in &mut events {
+        event.categories.sort();
+    }
+    events.sort_by(|a, b| a.chronological_cmp(b).then_with(|| a.content_cmp(b)));
+
+    let json = if options.include_interval {
+        let events: Vec<WithInterval> = events
+            .iter()
+            .map(|event| WithInterval {
+                event,
+                interval: event.iso_interval(),
+            })
+            .collect();
+        serde_json::to_string(&events)
+    } else {
+        serde_json::to_string(&events)
+    };
+    json.context("Failed to serialize events")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{all_day, date, event, timed};
+
+    #[test]
+    fn test_camel_case_field_names() {
+        let event = all_day("1", date(2025, 12, 10));
+
+        let camel = serde_json::to_string(&CamelCase(&event)).unwrap();
+        assert!(camel.contains("\"eventId\":\"1\""));
+        assert!(!camel.contains("event_id"));
+
+        let snake = serde_json::to_string(&event).unwrap();
+        assert!(snake.contains("\"event_id\":\"1\""));
+    }
+
+    #[test]
+    fn test_to_json_interval_field() {
+        let day = date(2025, 12, 10);
+        let events = vec![event("1", timed(day, 8, 30), timed(day, 9, 30))];
+
+        assert!(!to_json(&events).unwrap().contains("interval"));
+        let options = JsonOptions {
+            include_interval: true,
+        };
+        let json: serde_json::Value =
+            serde_json::from_str(&to_json_with_options(&events, &options).unwrap()).unwrap();
+        assert_eq!(json[0]["interval"], "2025-12-10T08:30/2025-12-10T09:30");
+        assert_eq!(json[0]["event_id"], "1");
+    }
+
+    #[test]
+    fn test_to_json_is_deterministic() {
+        let day = date(2025, 12, 10);
+        let mut chapel = event("2", timed(day, 8, 30), timed(day, 9, 0));
+        chapel.categories = vec!["Whole School".to_string(), "Chapel".to_string()];
+        let mut assembly = event("1", timed(day, 8, 30), timed(day, 9, 0));
+        assembly.categories = vec!["Senior".to_string(), "Assembly".to_string()];
+        let events = vec![chapel, all_day("3", day), assembly];
+
+        let mut reversed = events.clone();
+        reversed.reverse();
+
+        let first = to_json(&events).unwrap();
+        assert_eq!(first, to_json(&events).unwrap());
+        assert_eq!(first, to_json(&reversed).unwrap());
+
+        let ids: Vec<String> = serde_json::from_str::<Vec<CalendarEvent>>(&first)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.event_id.to_string())
+            .collect();
+        assert_eq!(ids, vec!["3", "1", "2"]);
+        assert!(first.contains(r#""categories":["Assembly","Senior"]"#));
+    }
+}
@@ -1,91 +1,64 @@
+#[cfg(feature = "binary")]
+pub mod binary;
+#[cfg(feature = "client")]
 pub mod client;
+pub mod diff;
+pub mod export;
+#[cfg(feature = "client")]
+pub mod fetch;
+pub mod filters;
+pub mod grouping;
+pub mod ics;
+pub mod json;
 pub mod models;
 pub mod parser;
+pub mod schedule;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+#[cfg(test)]
+mod test_utils;
 
-pub use client::fetch_calendar;
-pub use models::{CalendarEvent, EventTime};
-pub use parser::parse_calendar_xml;
-
-use anyhow::Context;
-use anyhow::Result;
-use chrono::NaiveDate;
-
-// need to make a recursive function that takes in a start and end date. and fetches all events between those dates
-// it has to be recursive because the API ends due to size limits
-/// Recursively fetches all calendar events between the given start and end dates.
-///
-/// This function handles the limitation of the SOCS API which may truncate results due to size limits.
-/// It fetches events in chunks, starting from the given start date and continuing until all events
-/// within the date range are retrieved. The function automatically handles pagination by using
-/// the date of the last retrieved event as the starting point for the next request.
-///
-/// Events are deduplicated by ID and sorted by start time before being returned.
-///
-/// # Arguments
-///
-/// * `base_url` - The base URL for the SOCS calendar API which you are given when you create a key
-/// * `start_date` - The start date for the event range (inclusive)
-/// * `end_date` - The end date for the event range (inclusive)
-///
-/// # Returns
-///
-/// Returns a `Result` containing a vector of `CalendarEvent`s if successful, or an error if the
-/// fetching or parsing fails.
-///
-/// # Examples
-///
-/// ```rust,no_run
-/// use chrono::NaiveDate;
-/// use socs_calendar_parser::fetch_events_recursive;
-///
-/// # async fn example() -> anyhow::Result<()> {
-/// let start = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
-/// let end = NaiveDate::from_ymd_opt(2025, 12, 31).unwrap();
-/// let events = fetch_events_recursive("https://www.socscms.com/socs/xml/SOCScalendar.ashx?ID={}key={}", start, end).await?;
-/// println!("Found {} events", events.len());
-/// # Ok(())
-/// # }
-/// ```
-pub async fn fetch_events_recursive(
-    base_url: &str,
-    start_date: NaiveDate,
-    end_date: NaiveDate,
-) -> Result<Vec<CalendarEvent>> {
-    let mut all_events = Vec::new();
-    let mut current_start = start_date;
-
-    loop {
-        // fetch events
-        let events = fetch_calendar(base_url, current_start, end_date).await?;
-        let events = parse_calendar_xml(events)?;
-
-        if events.is_empty() {
-            break;
-        }
-
-        let last_event_date = events
-            .last()
-            .map(|e| e.start.date())
-            .context("Failed to get last date")?;
-
-        all_events.extend(events);
-
-        // Stop if we've reached the end date
-        if last_event_date >= end_date {
-            break;
-        }
-
-        // Continue from the same day as the last event to avoid missing events
-        // The deduplication below will handle any duplicates
-        current_start = last_event_date;
-    }
-
-    // deduplicate events by id
-    all_events.sort_by(|a, b| a.event_id.cmp(&b.event_id));
-    all_events.dedup_by(|a, b| a.event_id == b.event_id);
-
-    // sort events by start date
-    all_events.sort_by(|a, b| a.start.cmp(&b.start));
-
-    Ok(all_events)
-}
+#[cfg(feature = "client")]
+pub use client::{
+    BoolEncoding, CachedResponse, CalendarFetcher, CalendarQuery, Feed, HttpFetcher, ResponseCache,
+    ResponseTooLarge, SocsClient, Visibility, fetch_calendar, fetch_calendar_cached,
+    fetch_calendar_with_client, fetch_calendar_with_headers, validate_credentials,
+};
+pub use diff::{CalendarDiff, EventChange, diff_events, format_diff};
+pub use export::{
+    CsvExporter, DEFAULT_COLUMN_WIDTH, EventExporter, FullCalendarExporter, IcsExporter,
+    render_week_ascii, render_week_ascii_with_width, to_outlook_csv,
+};
+#[cfg(feature = "client")]
+pub use fetch::{
+    EndInclusivity, FetchOptions, FetchOutcome, SkippedRange, SocsError, SourcedEvent,
+    count_events, fetch_all_day, fetch_events_best_effort, fetch_events_multi_tagged,
+    fetch_events_ranges, fetch_events_recursive, fetch_events_recursive_stream,
+    fetch_events_recursive_with_fetcher, fetch_events_recursive_with_options,
+    fetch_events_recursive_with_raw, fetch_events_window, fetch_today, preview_urls, watch,
+};
+pub use filters::{
+    SortOrder, all_day_only, between_times, dedup_in_place, dedup_merge_in_place, drop_cancelled,
+    into_chronological_queue, merge_events, normalize_locations, search, shift_events, sort_by_id,
+    sort_events, upcoming,
+};
+pub use grouping::{
+    WeeklyPattern, agenda_pages, busiest_day, category_durations, category_durations_with,
+    coalesce_by_category, detect_weekly_patterns, group_by_day, group_by_day_part, group_by_house,
+    group_by_week, hour_histogram, hour_histogram_spread,
+};
+pub use ics::{to_ical_weekly, to_ics};
+pub use json::{CamelCase, JsonOptions, to_json, to_json_with_options};
+pub use models::{
+    CalendarEvent, DayPart, DayPartBoundaries, EventId, EventStatus, EventTime, Priority,
+    RawFields, local_today,
+};
+pub use parser::{
+    CalendarMeta, ParseDiagnostic, ParseError, ParseMode, ParseOptions, ParsedCalendar, Severity,
+    looks_truncated, looks_truncated_with_meta, parse_calendar_auto, parse_calendar_file,
+    parse_calendar_xml, parse_calendar_xml_at, parse_calendar_xml_with_meta,
+    parse_calendar_xml_with_options,
+};
+pub use schedule::{
+    assign_lanes, coverage_gaps, free_slots, overlap, tight_transitions, time_until_next,
+};
@@ -1,60 +1,529 @@
-use chrono::{NaiveDate, NaiveTime};
+use crate::parser::sort_categories;
+use chrono::{
+    DateTime, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeDelta, TimeZone, Utc,
+};
+use chrono_tz::Tz;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::fmt;
+use std::sync::LazyLock;
 
+// The root element name isn't checked, so `<Calendar>` or `<Events>` roots work too
 #[derive(Debug, Clone, Deserialize)]
 pub struct SOCSCalendar {
-    #[serde(rename = "CalendarEvent", default)]
+    #[serde(rename = "Header", default)]
+    pub header: Option<CalendarHeaderXml>,
+
+    #[serde(rename = "CalendarEvent", alias = "Event", default)]
     pub events: Vec<CalendarEventXml>,
 }
 
+/// The range SOCS echoes back, as attributes or child elements of `<Header>`
+#[derive(Debug, Clone, Deserialize)]
+pub struct CalendarHeaderXml {
+    #[serde(rename = "@StartDate", alias = "StartDate", default)]
+    pub start_date: Option<String>,
+
+    #[serde(rename = "@EndDate", alias = "EndDate", default)]
+    pub end_date: Option<String>,
+
+    #[serde(rename = "@TotalCount", alias = "TotalCount", default)]
+    pub total_count: Option<String>,
+}
+
+/// One event as it appears in the feed
+///
+/// Fields may be child elements or attributes of `<CalendarEvent>`, since
+/// feeds have been seen using both shapes.
 #[derive(Debug, Clone, Deserialize)]
 pub struct CalendarEventXml {
-    #[serde(rename = "EventID")]
+    #[serde(rename = "EventID", alias = "@EventID")]
     pub event_id: String,
 
-    #[serde(rename = "StartDate")]
+    #[serde(rename = "StartDate", alias = "@StartDate")]
     pub start_date: String,
 
-    #[serde(rename = "EndDate")]
+    #[serde(rename = "EndDate", alias = "@EndDate")]
     pub end_date: String,
 
-    #[serde(rename = "StartTime")]
+    #[serde(rename = "StartTime", alias = "@StartTime")]
     pub start_time: String,
 
-    #[serde(rename = "EndTime")]
+    #[serde(rename = "EndTime", alias = "@EndTime")]
     pub end_time: Option<String>,
 
-    #[serde(rename = "Title")]
+    #[serde(rename = "Title", alias = "@Title")]
     pub title: String,
 
-    #[serde(rename = "Description")]
+    #[serde(rename = "Description", alias = "@Description")]
     pub description: Option<String>,
 
-    #[serde(rename = "Location")]
-    pub location: String,
+    #[serde(rename = "Location", alias = "@Location", default)]
+    pub location: Option<String>,
+
+    #[serde(rename = "Category", alias = "@Category", default)]
+    pub category: Option<String>,
+
+    #[serde(rename = "AllDayEvent", alias = "@AllDayEvent", default)]
+    pub all_day_event: Option<String>,
+
+    #[serde(
+        rename = "Modified",
+        alias = "LastUpdated",
+        alias = "@Modified",
+        alias = "@LastUpdated",
+        default
+    )]
+    pub modified: Option<String>,
 
-    #[serde(rename = "Category")]
-    pub category: String,
+    #[serde(
+        rename = "Priority",
+        alias = "Importance",
+        alias = "@Priority",
+        alias = "@Importance",
+        default
+    )]
+    pub priority: Option<String>,
+
+    #[serde(
+        rename = "URL",
+        alias = "Link",
+        alias = "@URL",
+        alias = "@Link",
+        default
+    )]
+    pub url: Option<String>,
+
+    #[serde(rename = "Status", alias = "@Status", default)]
+    pub status: Option<String>,
+
+    #[serde(rename = "Cancelled", alias = "@Cancelled", default)]
+    pub cancelled: Option<String>,
+
+    #[serde(
+        rename = "TimeZone",
+        alias = "Timezone",
+        alias = "@TimeZone",
+        alias = "@Timezone",
+        default
+    )]
+    pub timezone: Option<String>,
+
+    #[serde(
+        rename = "House",
+        alias = "Team",
+        alias = "@House",
+        alias = "@Team",
+        default
+    )]
+    pub group: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// The SOCS identifier for an event
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct EventId(pub String);
+
+impl EventId {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The ID as an integer, if it is purely numeric
+    pub fn as_u64(&self) -> Option<u64> {
+        self.0.trim().parse().ok()
+    }
+}
+
+impl From<String> for EventId {
+    fn from(id: String) -> Self {
+        EventId(id)
+    }
+}
+
+impl From<&str> for EventId {
+    fn from(id: &str) -> Self {
+        EventId(id.to_string())
+    }
+}
+
+impl PartialEq<str> for EventId {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for EventId {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl fmt::Display for EventId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CalendarEvent {
-    pub event_id: String,
+    pub event_id: EventId,
     pub title: String,
     pub description: Option<String>,
     pub location: String,
     pub categories: Vec<String>,
     pub start: EventTime,
     pub end: EventTime,
+    /// When the event was last changed in SOCS, if the feed says
+    #[serde(default)]
+    pub modified: Option<NaiveDateTime>,
+    /// How important SOCS marks the event, if the feed says
+    #[serde(default)]
+    pub priority: Option<Priority>,
+    /// A link back to the event in the SOCS web UI, if the feed gives a valid one
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Whether the event is going ahead; feeds without a status are confirmed
+    #[serde(default)]
+    pub status: EventStatus,
+    /// The time zone the feed tags the event with, for feeds that span schools
+    #[serde(default)]
+    pub timezone: Option<Tz>,
+    /// The house or team the event belongs to, for schools that organise by house
+    #[serde(default)]
+    pub group: Option<String>,
+    /// The feed's original date and time strings, kept when parsing with
+    /// [`ParseOptions::keep_raw`](crate::parser::ParseOptions::keep_raw)
+    #[serde(default)]
+    pub raw: Option<RawFields>,
+}
+
+/// The date and time strings of an event exactly as they appeared in the feed
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct RawFields {
+    pub start_date: String,
+    pub end_date: String,
+    pub start_time: String,
+    pub end_time: Option<String>,
+    pub all_day_event: Option<String>,
+}
+
+/// Whether an event is going ahead
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum EventStatus {
+    #[default]
+    Confirmed,
+    Cancelled,
+    Tentative,
+}
+
+/// How important an event is, e.g. for highlighting key dates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Priority {
+    High,
+    Normal,
+    Low,
+}
+
+/// A broad part of the day, for simplified summaries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DayPart {
+    Morning,
+    Afternoon,
+    Evening,
+}
+
+/// Where one [`DayPart`] ends and the next begins
+///
+/// A start before `afternoon` is morning, one before `evening` is afternoon,
+/// and anything from `evening` on is evening. The default splits at 12:00
+/// and 17:00.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DayPartBoundaries {
+    pub afternoon: NaiveTime,
+    pub evening: NaiveTime,
+}
+
+impl Default for DayPartBoundaries {
+    fn default() -> Self {
+        Self {
+            afternoon: NaiveTime::from_hms_opt(12, 0, 0).unwrap(),
+            evening: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+        }
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum EventTime {
     AllDay(NaiveDate),
     Specific { date: NaiveDate, time: NaiveTime },
 }
 
+impl CalendarEvent {
+    /// Compare events chronologically by start, then end, falling back to the
+    /// event ID so the ordering is total and stable across fetches
+    pub fn chronological_cmp(&self, other: &Self) -> Ordering {
+        self.start
+            .cmp(&other.start)
+            .then_with(|| self.end.cmp(&other.end))
+            .then_with(|| self.event_id.cmp(&other.event_id))
+    }
+
+    /// Compare every field other than the ID, giving a total order over
+    /// events that share one
+    pub(crate) fn content_cmp(&self, other: &Self) -> Ordering {
+        self.start
+            .cmp(&other.start)
+            .then_with(|| self.end.cmp(&other.end))
+            .then_with(|| self.title.cmp(&other.title))
+            .then_with(|| self.location.cmp(&other.location))
+            .then_with(|| self.description.cmp(&other.description))
+            .then_with(|| self.categories.cmp(&other.categories))
+            .then_with(|| self.modified.cmp(&other.modified))
+            .then_with(|| self.priority.cmp(&other.priority))
+            .then_with(|| self.url.cmp(&other.url))
+            .then_with(|| self.status.cmp(&other.status))
+            .then_with(|| {
+                let name = |event: &Self| event.timezone.map(|tz| tz.name());
+                name(self).cmp(&name(other))
+            })
+            .then_with(|| self.group.cmp(&other.group))
+            .then_with(|| self.raw.cmp(&other.raw))
+    }
+
+    /// A canonical copy of the event for comparing fetches against each other
+    ///
+    /// The raw feed strings are dropped, and runs of whitespace in text fields
+    /// are collapsed to single spaces (with an empty description becoming
+    /// `None`), categories are sorted and deduplicated ignoring case, and the
+    /// end is made consistent with the start: all-day events end as all-day,
+    /// and a timed end before the start is clamped to the start.
+    pub fn normalized(&self) -> CalendarEvent {
+        let mut categories: Vec<String> = self
+            .categories
+            .iter()
+            .map(|c| collapse_whitespace(c))
+            .filter(|c| !c.is_empty())
+            .collect();
+        sort_categories(&mut categories);
+
+        let end = match (&self.start, &self.end) {
+            (EventTime::AllDay(_), end) => EventTime::AllDay(end.date()),
+            (start, end) if end < start => start.clone(),
+            (_, end) => end.clone(),
+        };
+
+        CalendarEvent {
+            event_id: EventId(self.event_id.as_str().trim().to_string()),
+            title: collapse_whitespace(&self.title),
+            description: self
+                .description
+                .as_deref()
+                .map(collapse_whitespace)
+                .filter(|d| !d.is_empty()),
+            location: collapse_whitespace(&self.location),
+            categories,
+            start: self.start.clone(),
+            end,
+            modified: self.modified,
+            priority: self.priority,
+            url: self.url.clone(),
+            status: self.status,
+            timezone: self.timezone,
+            group: self
+                .group
+                .as_deref()
+                .map(collapse_whitespace)
+                .filter(|g| !g.is_empty()),
+            raw: None,
+        }
+    }
+
+    /// The local start and end of the event, with all-day events running
+    /// midnight to midnight and an end before the start clamped to it
+    pub(crate) fn local_span(&self) -> (NaiveDateTime, NaiveDateTime) {
+        let start = match &self.start {
+            EventTime::AllDay(date) => date.and_time(NaiveTime::MIN),
+            EventTime::Specific { date, time } => date.and_time(*time),
+        };
+        let end = match &self.end {
+            EventTime::AllDay(date) => date.succ_opt().unwrap_or(*date).and_time(NaiveTime::MIN),
+            EventTime::Specific { date, time } => date.and_time(*time),
+        };
+        (start, end.max(start))
+    }
+
+    /// How long the event runs for, with all-day events lasting 24 hours per day
+    pub fn duration(&self) -> TimeDelta {
+        let (start, end) = self.local_span();
+        end - start
+    }
+
+    /// The start of the event in UTC, reading its local time in the school's time zone
+    ///
+    /// An event tagged with its own [`timezone`](Self::timezone) is read in
+    /// that zone instead of `tz`. All-day events start at local midnight on
+    /// their start date. Around clock changes, a repeated local time maps to
+    /// its first occurrence and a skipped local time is moved forward past
+    /// the gap.
+    pub fn start_utc(&self, tz: Tz) -> DateTime<Utc> {
+        let local = match &self.start {
+            EventTime::AllDay(date) => date.and_time(NaiveTime::MIN),
+            EventTime::Specific { date, time } => date.and_time(*time),
+        };
+        local_to_utc(local, self.timezone.unwrap_or(tz))
+    }
+
+    /// The end of the event in UTC, reading its local time in the school's time zone
+    ///
+    /// All-day events end at local midnight at the close of their end date.
+    /// The event's own time zone and clock changes are handled as for
+    /// [`start_utc`](Self::start_utc).
+    pub fn end_utc(&self, tz: Tz) -> DateTime<Utc> {
+        let local = match &self.end {
+            EventTime::AllDay(date) => date.succ_opt().unwrap_or(*date).and_time(NaiveTime::MIN),
+            EventTime::Specific { date, time } => date.and_time(*time),
+        };
+        local_to_utc(local, self.timezone.unwrap_or(tz))
+    }
+
+    /// The http(s) URLs mentioned in the description, in order of appearance
+    ///
+    /// Punctuation that ends a sentence or closes brackets around a link, as in
+    /// `(see https://example.com/map).`, is not treated as part of it.
+    pub fn links(&self) -> Vec<String> {
+        static URL: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r#"https?://[^\s<>"']+"#).unwrap());
+
+        let Some(description) = &self.description else {
+            return Vec::new();
+        };
+        URL.find_iter(description)
+            .map(|m| trim_url(m.as_str()).to_string())
+            .collect()
+    }
+
+    /// The rooms in `location`, split on commas and trimmed
+    ///
+    /// This is best effort: SOCS has no separator for multiple rooms, so a
+    /// single name that contains a comma ("Hall, North Wing") is split too.
+    /// The raw `location` is left untouched.
+    pub fn location_list(&self) -> Vec<String> {
+        self.location
+            .split(',')
+            .map(str::trim)
+            .filter(|room| !room.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// The categories joined with `sep`, e.g. "Rugby · U15 · Away"
+    ///
+    /// Returns an empty string when the event has no categories.
+    pub fn categories_display(&self, sep: &str) -> String {
+        self.categories.join(sep)
+    }
+
+    /// The event's span as an ISO 8601 interval
+    ///
+    /// Timed events give local date-times, e.g.
+    /// `2025-12-10T08:30/2025-12-10T09:30`; all-day events give their first
+    /// and last dates, e.g. `2025-12-10/2025-12-12`.
+    pub fn iso_interval(&self) -> String {
+        let format = |time: &EventTime| match time {
+            EventTime::AllDay(date) => date.format("%Y-%m-%d").to_string(),
+            EventTime::Specific { date, time } => {
+                date.and_time(*time).format("%Y-%m-%dT%H:%M").to_string()
+            }
+        };
+        format!("{}/{}", format(&self.start), format(&self.end))
+    }
+
+    /// The part of the day the event starts in, or `None` for all-day events
+    ///
+    /// Uses the default [`DayPartBoundaries`] of 12:00 and 17:00.
+    pub fn day_part(&self) -> Option<DayPart> {
+        self.day_part_with(&DayPartBoundaries::default())
+    }
+
+    /// The part of the day the event starts in, split at the given boundaries
+    pub fn day_part_with(&self, boundaries: &DayPartBoundaries) -> Option<DayPart> {
+        let EventTime::Specific { time, .. } = &self.start else {
+            return None;
+        };
+        Some(if *time < boundaries.afternoon {
+            DayPart::Morning
+        } else if *time < boundaries.evening {
+            DayPart::Afternoon
+        } else {
+            DayPart::Evening
+        })
+    }
+
+    /// How many calendar days the event touches, counting both the start and end date
+    ///
+    /// A single-day event returns 1. An end before the start also counts as one day.
+    pub fn day_count(&self) -> u32 {
+        let days = (self.end.date() - self.start.date()).num_days().max(0);
+        days as u32 + 1
+    }
+}
+
+/// Strip trailing punctuation, keeping a closing bracket the URL itself opened
+fn trim_url(url: &str) -> &str {
+    let mut url = url;
+    loop {
+        let trimmed = url.trim_end_matches(['.', ',', ';', ':', '!', '?']);
+        let trimmed = match trimmed.strip_suffix(')') {
+            Some(inner) if inner.matches('(').count() < trimmed.matches(')').count() => inner,
+            _ => trimmed,
+        };
+        if trimmed.len() == url.len() {
+            return url;
+        }
+        url = trimmed;
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Today's date at the school, in its time zone rather than the server's
+///
+/// Near midnight the school's date can differ from UTC's, e.g. 23:30 UTC in
+/// British Summer Time is already tomorrow in London.
+pub fn local_today(tz: Tz) -> NaiveDate {
+    local_date_at(Utc::now(), tz)
+}
+
+fn local_date_at(instant: DateTime<Utc>, tz: Tz) -> NaiveDate {
+    instant.with_timezone(&tz).date_naive()
+}
+
+/// Convert a naive local time in `tz` to UTC
+///
+/// Clock changes are resolved deterministically: a time that occurs twice
+/// when the clocks go back maps to its first occurrence, and a time skipped
+/// when the clocks go forward (e.g. 01:30 on the UK spring-forward night) is
+/// read with the offset in force before the change, landing just after the gap.
+fn local_to_utc(local: NaiveDateTime, tz: Tz) -> DateTime<Utc> {
+    match tz.from_local_datetime(&local) {
+        LocalResult::Single(dt) | LocalResult::Ambiguous(dt, _) => dt.with_timezone(&Utc),
+        LocalResult::None => {
+            // Gaps are at most a few hours, so this is safely before the change
+            let offset = tz
+                .from_local_datetime(&(local - TimeDelta::hours(3)))
+                .earliest()
+                .map_or(0, |dt| dt.offset().fix().local_minus_utc());
+            Utc.from_utc_datetime(&(local - TimeDelta::seconds(offset.into())))
+        }
+    }
+}
+
 impl EventTime {
     pub fn date(&self) -> NaiveDate {
         match self {
@@ -68,6 +537,23 @@ impl EventTime {
     }
 }
 
+// Order by date first, with all-day events ahead of timed events on the same day
+impl Ord for EventTime {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let key = |t: &EventTime| match t {
+            EventTime::AllDay(date) => (*date, None),
+            EventTime::Specific { date, time } => (*date, Some(*time)),
+        };
+        key(self).cmp(&key(other))
+    }
+}
+
+impl PartialOrd for EventTime {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl fmt::Display for EventTime {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -78,3 +564,210 @@ impl fmt::Display for EventTime {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{all_day, date, event, time, timed};
+
+    #[test]
+    fn test_event_time_orders_by_date_before_variant() {
+        let all_day_later = EventTime::AllDay(date(2025, 12, 11));
+        let timed_earlier = timed(date(2025, 12, 10), 8, 30);
+        assert!(timed_earlier < all_day_later);
+
+        let all_day_same = EventTime::AllDay(date(2025, 12, 10));
+        assert!(all_day_same < timed_earlier);
+    }
+
+    #[test]
+    fn test_normalized_ignores_whitespace_and_category_order() {
+        let day = date(2025, 12, 10);
+        let mut a = event("1", timed(day, 14, 0), timed(day, 16, 0));
+        a.title = "Rugby  v\tSt Olave's ".to_string();
+        a.categories = vec!["U15".to_string(), "Rugby".to_string()];
+
+        let mut b = event("1", timed(day, 14, 0), timed(day, 16, 0));
+        b.title = "Rugby v St Olave's".to_string();
+        b.categories = vec!["Rugby".to_string(), " U15".to_string(), "rugby".to_string()];
+
+        assert_ne!(a, b);
+        assert_eq!(a.normalized(), b.normalized());
+    }
+
+    #[test]
+    fn test_utc_conversion_in_bst() {
+        let day = date(2025, 7, 1);
+        let event = event("1", timed(day, 8, 30), timed(day, 9, 30));
+        let start = event.start_utc(chrono_tz::Europe::London);
+        let end = event.end_utc(chrono_tz::Europe::London);
+        assert_eq!(start.naive_utc(), day.and_hms_opt(7, 30, 0).unwrap());
+        assert_eq!(end.naive_utc(), day.and_hms_opt(8, 30, 0).unwrap());
+    }
+
+    #[test]
+    fn test_utc_conversion_in_gmt() {
+        let day = date(2025, 12, 10);
+        let event = event("1", timed(day, 8, 30), timed(day, 9, 30));
+        let start = event.start_utc(chrono_tz::Europe::London);
+        assert_eq!(start.naive_utc(), day.and_hms_opt(8, 30, 0).unwrap());
+    }
+
+    #[test]
+    fn test_utc_conversion_in_spring_forward_gap() {
+        // Clocks went from 01:00 GMT to 02:00 BST on 30 March 2025
+        let day = date(2025, 3, 30);
+        let event = event("1", timed(day, 1, 30), timed(day, 2, 30));
+        let start = event.start_utc(chrono_tz::Europe::London);
+        let end = event.end_utc(chrono_tz::Europe::London);
+        assert_eq!(start.naive_utc(), day.and_hms_opt(1, 30, 0).unwrap());
+        assert_eq!(end.naive_utc(), day.and_hms_opt(1, 30, 0).unwrap());
+    }
+
+    #[test]
+    fn test_utc_conversion_in_fall_back_fold() {
+        // 01:30 happened twice on 26 October 2025; the BST occurrence comes first
+        let day = date(2025, 10, 26);
+        let event = event("1", timed(day, 1, 30), timed(day, 2, 30));
+        let start = event.start_utc(chrono_tz::Europe::London);
+        assert_eq!(start.naive_utc(), day.and_hms_opt(0, 30, 0).unwrap());
+    }
+
+    #[test]
+    fn test_utc_conversion_all_day() {
+        let day = date(2025, 7, 1);
+        let event = all_day("1", day);
+        let start = event.start_utc(chrono_tz::Europe::London);
+        let end = event.end_utc(chrono_tz::Europe::London);
+        assert_eq!(
+            start.naive_utc(),
+            date(2025, 6, 30).and_hms_opt(23, 0, 0).unwrap()
+        );
+        assert_eq!(end.naive_utc(), day.and_hms_opt(23, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_day_count() {
+        let day = date(2025, 12, 10);
+        assert_eq!(
+            event("1", timed(day, 8, 30), timed(day, 9, 0)).day_count(),
+            1
+        );
+        assert_eq!(
+            event("2", timed(day, 19, 0), timed(date(2025, 12, 11), 7, 0)).day_count(),
+            2
+        );
+
+        let mut trip = all_day("3", date(2025, 12, 8));
+        trip.end = EventTime::AllDay(date(2025, 12, 14));
+        assert_eq!(trip.day_count(), 7);
+    }
+
+    #[test]
+    fn test_links_from_description() {
+        let mut fixture = all_day("1", date(2025, 12, 10));
+        fixture.description = Some(
+            "Team sheet: https://example.com/sheets/u15.pdf. Directions \
+             (map at http://maps.example.com/?q=Eton+College), kick off 2pm!"
+                .to_string(),
+        );
+
+        assert_eq!(
+            fixture.links(),
+            vec![
+                "https://example.com/sheets/u15.pdf",
+                "http://maps.example.com/?q=Eton+College",
+            ]
+        );
+
+        fixture.description = Some("See https://en.wikipedia.org/wiki/Eton_(town).".to_string());
+        assert_eq!(
+            fixture.links(),
+            vec!["https://en.wikipedia.org/wiki/Eton_(town)"]
+        );
+
+        fixture.description = None;
+        assert!(fixture.links().is_empty());
+    }
+
+    #[test]
+    fn test_location_list() {
+        let mut fixture = all_day("1", date(2025, 12, 10));
+        fixture.location = " Sports Hall,Astro , ".to_string();
+        assert_eq!(fixture.location_list(), vec!["Sports Hall", "Astro"]);
+        assert_eq!(fixture.location, " Sports Hall,Astro , ");
+
+        fixture.location = String::new();
+        assert!(fixture.location_list().is_empty());
+    }
+
+    #[test]
+    fn test_iso_interval() {
+        let day = date(2025, 12, 10);
+        let timed_event = event("1", timed(day, 8, 30), timed(day, 9, 30));
+        assert_eq!(
+            timed_event.iso_interval(),
+            "2025-12-10T08:30/2025-12-10T09:30"
+        );
+
+        let mut trip = all_day("2", day);
+        trip.end = EventTime::AllDay(date(2025, 12, 12));
+        assert_eq!(trip.iso_interval(), "2025-12-10/2025-12-12");
+    }
+
+    #[test]
+    fn test_day_part_boundaries() {
+        let day = date(2025, 12, 10);
+        let starting = |hour, minute| event("1", timed(day, hour, minute), timed(day, 23, 0));
+
+        assert_eq!(starting(11, 59).day_part(), Some(DayPart::Morning));
+        assert_eq!(starting(12, 0).day_part(), Some(DayPart::Afternoon));
+        assert_eq!(starting(16, 59).day_part(), Some(DayPart::Afternoon));
+        assert_eq!(starting(17, 0).day_part(), Some(DayPart::Evening));
+        assert_eq!(all_day("2", day).day_part(), None);
+
+        let boundaries = DayPartBoundaries {
+            afternoon: time(13, 0),
+            evening: time(18, 0),
+        };
+        assert_eq!(
+            starting(12, 0).day_part_with(&boundaries),
+            Some(DayPart::Morning)
+        );
+        assert_eq!(
+            starting(17, 0).day_part_with(&boundaries),
+            Some(DayPart::Afternoon)
+        );
+    }
+
+    #[test]
+    fn test_categories_display() {
+        let mut fixture = all_day("1", date(2025, 12, 10));
+        assert_eq!(fixture.categories_display(" · "), "");
+
+        fixture.categories = vec!["Rugby".to_string()];
+        assert_eq!(fixture.categories_display(" · "), "Rugby");
+
+        fixture
+            .categories
+            .extend(["U15".to_string(), "Away".to_string()]);
+        assert_eq!(fixture.categories_display(" · "), "Rugby · U15 · Away");
+    }
+
+    #[test]
+    fn test_local_date_differs_from_utc_near_midnight() {
+        // 23:30 UTC on 1 July is 00:30 on 2 July in London (BST)
+        let instant = date(2025, 7, 1).and_hms_opt(23, 30, 0).unwrap().and_utc();
+        assert_eq!(
+            local_date_at(instant, chrono_tz::Europe::London),
+            date(2025, 7, 2)
+        );
+
+        // 00:30 UTC on 2 July is still 1 July in New York
+        let instant = date(2025, 7, 2).and_hms_opt(0, 30, 0).unwrap().and_utc();
+        assert_eq!(
+            local_date_at(instant, chrono_tz::America::New_York),
+            date(2025, 7, 1)
+        );
+    }
+}
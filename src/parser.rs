@@ -1,29 +1,471 @@
-use crate::models::{CalendarEvent, CalendarEventXml, EventTime, SOCSCalendar};
+use crate::models::{
+    CalendarEvent, CalendarEventXml, CalendarHeaderXml, EventId, EventStatus, EventTime, Priority,
+    RawFields, SOCSCalendar,
+};
 use anyhow::{Context, Result};
-use chrono::{NaiveDate, NaiveTime};
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
+use chrono_tz::Tz;
+use flate2::read::GzDecoder;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+/// How strictly malformed events are handled while parsing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Fail the whole parse on the first malformed event
+    #[default]
+    Strict,
+    /// Keep events whose secondary fields (location, categories) are malformed,
+    /// defaulting those fields and recording a diagnostic. Events that can't be
+    /// parsed at all are skipped and recorded in [`ParsedCalendar::errors`].
+    Lenient,
+}
+
+/// Options controlling how calendar XML is parsed
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    pub mode: ParseMode,
+    /// Sort each event's categories alphabetically (ignoring case) and drop
+    /// duplicates, rather than keeping the feed's order
+    pub sort_categories: bool,
+    /// Keep each event's original date and time strings in [`CalendarEvent::raw`]
+    pub keep_raw: bool,
+    /// A chrono format string (e.g. `%I.%M%p` for "8.30am") to read times
+    /// with instead of the default `HH:MM`; "All Day" is still recognised
+    pub time_format: Option<String>,
+}
+
+/// How serious a parse diagnostic is, ordered from least to most severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The event was kept, but something about it looks off (e.g. an empty title)
+    Warning,
+    /// The event couldn't be read at all and was skipped (e.g. a bad date)
+    Error,
+}
+
+/// A problem encountered while parsing an event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDiagnostic {
+    pub event_id: EventId,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// An event that couldn't be parsed and was left out in lenient mode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub event_id: EventId,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Event {}: {}", self.event_id, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// The request details SOCS echoes in a response's `<Header>` element
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalendarMeta {
+    pub requested_start: Option<NaiveDate>,
+    pub requested_end: Option<NaiveDate>,
+    /// How many events SOCS says match the range, which can be compared with
+    /// the number parsed to spot a truncated response
+    pub total_count: Option<usize>,
+}
+
+/// How many events make a page look full enough to have been cut off
+const FULL_PAGE_EVENTS: usize = 50;
+
+/// How far short of the requested end a full page must stop to look cut off
+const TRUNCATION_SHORTFALL_DAYS: i64 = 7;
+
+/// Guess whether a parsed page was truncated by the API's size limit
+///
+/// A page looks truncated when it holds a lot of events (50 or more) yet the
+/// last of them starts at least a week before `requested_end`. This is a
+/// heuristic: a busy term followed by a quiet holiday looks the same. See
+/// [`looks_truncated_with_meta`] to check against a reported total instead.
+pub fn looks_truncated(events: &[CalendarEvent], requested_end: NaiveDate) -> bool {
+    let Some(last) = events.iter().map(|event| event.start.date()).max() else {
+        return false;
+    };
+    events.len() >= FULL_PAGE_EVENTS
+        && (requested_end - last).num_days() >= TRUNCATION_SHORTFALL_DAYS
+}
+
+/// Like [`looks_truncated`], but trusting the header's total count when there is one
+///
+/// With a [`CalendarMeta::total_count`], the page is truncated exactly when
+/// it holds fewer events than that total.
+pub fn looks_truncated_with_meta(
+    events: &[CalendarEvent],
+    requested_end: NaiveDate,
+    meta: Option<&CalendarMeta>,
+) -> bool {
+    match meta.and_then(|meta| meta.total_count) {
+        Some(total) => total > events.len(),
+        None => looks_truncated(events, requested_end),
+    }
+}
+
+/// Parsed events along with any diagnostics recorded while parsing them
+#[derive(Debug, Clone, Default)]
+pub struct ParsedCalendar {
+    pub events: Vec<CalendarEvent>,
+    pub diagnostics: Vec<ParseDiagnostic>,
+    /// Events skipped in [`ParseMode::Lenient`] because they couldn't be parsed
+    pub errors: Vec<ParseError>,
+    /// The response header, if it had one that could be read
+    pub meta: Option<CalendarMeta>,
+}
+
+impl ParsedCalendar {
+    /// The diagnostics at `min` severity or above, in the order they were recorded
+    pub fn diagnostics_at_least(&self, min: Severity) -> impl Iterator<Item = &ParseDiagnostic> {
+        self.diagnostics.iter().filter(move |d| d.severity >= min)
+    }
+}
 
 /// Parse XML calendar data into structured events
 pub fn parse_calendar_xml(xml_data: String) -> Result<Vec<CalendarEvent>> {
-    let calendar: SOCSCalendar = serde_xml_rs::from_str(&xml_data.to_string())
-        .context("Failed to parse XML calendar data")?;
+    parse_calendar_xml_with_options(&xml_data, &ParseOptions::default()).map(|parsed| parsed.events)
+}
+
+/// Parse XML calendar data into structured events using the given options
+pub fn parse_calendar_xml_with_options(
+    xml_data: &str,
+    options: &ParseOptions,
+) -> Result<ParsedCalendar> {
+    let calendar: SOCSCalendar =
+        serde_xml_rs::from_str(xml_data).context("Failed to parse XML calendar data")?;
 
-    calendar.events.into_iter().map(parse_event).collect()
+    let mut parsed = ParsedCalendar {
+        meta: calendar.header.as_ref().map(parse_meta),
+        ..ParsedCalendar::default()
+    };
+    for event in calendar.events {
+        let event_id = EventId::from(event.event_id.clone());
+        match parse_event(event, options, &mut parsed.diagnostics) {
+            Ok(event) => parsed.events.push(event),
+            Err(error) if options.mode == ParseMode::Lenient => {
+                let message = format!("{:#}", error);
+                parsed.diagnostics.push(ParseDiagnostic {
+                    event_id: event_id.clone(),
+                    severity: Severity::Error,
+                    message: message.clone(),
+                });
+                parsed.errors.push(ParseError { event_id, message });
+            }
+            Err(error) => return Err(error),
+        }
+    }
+
+    // Some feeds list the same event twice in one response
+    dedup_by_content(&mut parsed.events);
+
+    Ok(parsed)
 }
 
-fn parse_event(event: CalendarEventXml) -> Result<CalendarEvent> {
+/// Keep one event per ID, where the ID first appeared
+///
+/// Of differing copies, the one that sorts first by
+/// [`CalendarEvent::content_cmp`] survives, so the result doesn't depend on
+/// the order the feed listed them in.
+fn dedup_by_content(events: &mut Vec<CalendarEvent>) {
+    let mut first_index: HashMap<EventId, usize> = HashMap::new();
+    let mut kept: Vec<CalendarEvent> = Vec::with_capacity(events.len());
+    for event in events.drain(..) {
+        match first_index.get(&event.event_id) {
+            Some(&index) => {
+                if event.content_cmp(&kept[index]).is_lt() {
+                    kept[index] = event;
+                }
+            }
+            None => {
+                first_index.insert(event.event_id.clone(), kept.len());
+                kept.push(event);
+            }
+        }
+    }
+    *events = kept;
+}
+
+/// Parse XML calendar data along with the range echoed in its header
+///
+/// The metadata is `None` when the response has no `<Header>` element. A
+/// header value that is missing or can't be read is left as `None`; that
+/// never fails the parse.
+pub fn parse_calendar_xml_with_meta(
+    xml_data: &str,
+) -> Result<(Vec<CalendarEvent>, Option<CalendarMeta>)> {
+    parse_calendar_xml_with_options(xml_data, &ParseOptions::default())
+        .map(|parsed| (parsed.events, parsed.meta))
+}
+
+fn parse_meta(header: &CalendarHeaderXml) -> CalendarMeta {
+    let date = |value: &Option<String>| value.as_deref().and_then(|d| parse_date(d).ok());
+    CalendarMeta {
+        requested_start: date(&header.start_date),
+        requested_end: date(&header.end_date),
+        total_count: header
+            .total_count
+            .as_deref()
+            .and_then(|count| count.trim().parse().ok()),
+    }
+}
+
+/// Parse calendar XML saved to disk, such as a cached API response
+///
+/// Files starting with the gzip magic bytes (e.g. `cached.xml.gz`) are
+/// decompressed first; anything else is read as plain XML.
+pub fn parse_calendar_file(path: impl AsRef<Path>) -> Result<Vec<CalendarEvent>> {
+    let path = path.as_ref();
+    let bytes =
+        std::fs::read(path).context(format!("Failed to read calendar file: {}", path.display()))?;
+
+    let xml = if bytes.starts_with(&[0x1f, 0x8b]) {
+        let mut xml = String::new();
+        GzDecoder::new(&bytes[..])
+            .read_to_string(&mut xml)
+            .context(format!(
+                "Failed to decompress calendar file: {}",
+                path.display()
+            ))?;
+        xml
+    } else {
+        String::from_utf8(bytes).context(format!(
+            "Calendar file is not valid UTF-8: {}",
+            path.display()
+        ))?
+    };
+
+    parse_calendar_xml(xml)
+}
+
+/// Fields checked, in order, for the XML inside a JSON envelope
+const ENVELOPE_FIELDS: [&str; 4] = ["data", "xml", "body", "content"];
+
+/// Parse a response body that is either raw calendar XML or XML wrapped in JSON
+///
+/// Some mirrors return `{"data": "<SOCSCalendar>...</SOCSCalendar>"}`. If the
+/// body is a JSON object with one of the recognised string fields (`data`,
+/// `xml`, `body` or `content`) holding XML, that XML is parsed; anything else
+/// is parsed as raw XML.
+pub fn parse_calendar_auto(body: &str) -> Result<Vec<CalendarEvent>> {
+    parse_calendar_auto_with_meta(body).map(|(events, _)| events)
+}
+
+/// Parse a body like [`parse_calendar_auto`], along with the range echoed in its header
+pub(crate) fn parse_calendar_auto_with_meta(
+    body: &str,
+) -> Result<(Vec<CalendarEvent>, Option<CalendarMeta>)> {
+    match unwrap_json_envelope(body) {
+        Some(xml) => parse_calendar_xml_with_meta(&xml),
+        None => parse_calendar_xml_with_meta(body),
+    }
+}
+
+fn unwrap_json_envelope(body: &str) -> Option<String> {
+    if !body.trim_start().starts_with('{') {
+        return None;
+    }
+    let value: serde_json::Value = serde_json::from_str(body).ok()?;
+    ENVELOPE_FIELDS.iter().find_map(|field| {
+        let inner = value.get(field)?.as_str()?;
+        inner
+            .trim_start()
+            .starts_with('<')
+            .then(|| inner.to_string())
+    })
+}
+
+/// Parse the calendar held in the first `<root_tag>` element of a larger document
+///
+/// Everything outside that element is ignored, so a SOCS calendar nested
+/// inside another integration's XML can be parsed without extracting it first.
+/// The element is located textually and must not contain another element
+/// with the same name.
+pub fn parse_calendar_xml_at(xml_data: &str, root_tag: &str) -> Result<Vec<CalendarEvent>> {
+    let element = find_element(xml_data, root_tag)
+        .context(format!("Element <{}> not found in XML", root_tag))?;
+
+    parse_calendar_xml_with_options(element, &ParseOptions::default()).map(|parsed| parsed.events)
+}
+
+/// Find the text of the first `<tag>...</tag>` (or `<tag/>`) element in `xml`
+fn find_element<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+
+    let mut search_from = 0;
+    let start = loop {
+        let index = xml[search_from..].find(&open)? + search_from;
+        let rest = &xml[index + open.len()..];
+        // Make sure we matched the whole tag name, not just a prefix of it
+        if rest.starts_with(['>', '/']) || rest.starts_with(char::is_whitespace) {
+            break index;
+        }
+        search_from = index + open.len();
+    };
+
+    let open_end = xml[start..].find('>')? + start;
+    if xml[..open_end].ends_with('/') {
+        return Some(&xml[start..=open_end]);
+    }
+
+    let close_start = xml[open_end..].find(&close)? + open_end;
+    Some(&xml[start..close_start + close.len()])
+}
+
+fn parse_event(
+    event: CalendarEventXml,
+    options: &ParseOptions,
+    diagnostics: &mut Vec<ParseDiagnostic>,
+) -> Result<CalendarEvent> {
     let start_date = parse_date(&event.start_date)
         .context(format!("Failed to parse start date: {}", event.start_date))?;
 
-    let end_date = parse_date(&event.end_date)
-        .context(format!("Failed to parse end date: {}", event.end_date))?;
+    // Minimal entries sometimes leave EndDate blank for a single-day event
+    let end_date = if event.end_date.trim().is_empty() {
+        start_date
+    } else {
+        parse_date(&event.end_date)
+            .context(format!("Failed to parse end date: {}", event.end_date))?
+    };
+
+    // An explicit AllDayEvent flag wins over whatever the time strings say
+    let time_format = options.time_format.as_deref();
+    let (start, end) = match event.all_day_event.as_deref().and_then(parse_bool) {
+        Some(true) => (EventTime::AllDay(start_date), EventTime::AllDay(end_date)),
+        Some(false) => {
+            // "All Day" or blank times then stand for the start and end of the day
+            let timed = |event_time: EventTime, time: NaiveTime| match event_time {
+                EventTime::AllDay(date) => EventTime::Specific { date, time },
+                event_time => event_time,
+            };
+            let (start, end) = parse_event_span(&event, start_date, end_date, time_format)?;
+            (timed(start, NaiveTime::MIN), timed(end, END_OF_DAY))
+        }
+        None => parse_event_span(&event, start_date, end_date, time_format)?,
+    };
 
-    let start = parse_event_time(start_date, &event.start_time)
+    let mut secondary_field = |value: Option<String>, name: &str| -> Result<String> {
+        match (value, options.mode) {
+            (Some(value), _) => Ok(value),
+            (None, ParseMode::Strict) => {
+                anyhow::bail!("Missing {} for event: {}", name, event.event_id)
+            }
+            (None, ParseMode::Lenient) => {
+                diagnostics.push(ParseDiagnostic {
+                    event_id: event.event_id.clone().into(),
+                    severity: Severity::Warning,
+                    message: format!("Missing {}, defaulted to empty", name),
+                });
+                Ok(String::new())
+            }
+        }
+    };
+
+    let location = secondary_field(event.location, "location")?;
+    let category = secondary_field(event.category, "category")?;
+
+    // Parse categories - comma-separated
+    let mut categories: Vec<String> = category
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    if options.sort_categories {
+        sort_categories(&mut categories);
+    }
+
+    if event.title.trim().is_empty() {
+        diagnostics.push(ParseDiagnostic {
+            event_id: event.event_id.clone().into(),
+            severity: Severity::Warning,
+            message: "Empty title".to_string(),
+        });
+    }
+
+    // An unreadable timestamp isn't worth failing the event over
+    let modified = event.modified.as_deref().and_then(parse_modified);
+    let priority = event.priority.as_deref().and_then(parse_priority);
+    let url = event.url.as_deref().and_then(parse_url);
+    let status = parse_status(event.status.as_deref(), event.cancelled.as_deref());
+    // An unknown zone name is dropped rather than failing the event
+    let timezone = event
+        .timezone
+        .as_deref()
+        .and_then(|name| name.trim().parse::<Tz>().ok());
+    let group = event
+        .group
+        .map(|group| group.trim().to_string())
+        .filter(|group| !group.is_empty());
+    let raw = options.keep_raw.then(|| RawFields {
+        start_date: event.start_date.clone(),
+        end_date: event.end_date.clone(),
+        start_time: event.start_time.clone(),
+        end_time: event.end_time.clone(),
+        all_day_event: event.all_day_event.clone(),
+    });
+
+    Ok(CalendarEvent {
+        event_id: event.event_id.into(),
+        title: event.title,
+        description: event.description,
+        location,
+        categories,
+        start,
+        end,
+        modified,
+        priority,
+        url,
+        status,
+        timezone,
+        group,
+        raw,
+    })
+}
+
+/// Sort categories alphabetically ignoring case, removing case-insensitive duplicates
+pub(crate) fn sort_categories(categories: &mut Vec<String>) {
+    categories.sort_by_key(|c| c.to_lowercase());
+    categories.dedup_by(|a, b| a.to_lowercase() == b.to_lowercase());
+}
+
+/// Work out the start and end of an event from its time strings
+///
+/// A timed start with an `EndTime` of "All Day" keeps its start time and runs
+/// to the end of the end date (23:59), rather than mixing a timed start with
+/// an all-day end.
+fn parse_event_span(
+    event: &CalendarEventXml,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    time_format: Option<&str>,
+) -> Result<(EventTime, EventTime)> {
+    let start = parse_event_time(start_date, &event.start_time, time_format)
         .context(format!("Failed to parse start time: {}", event.start_time))?;
 
     let end = if let Some(end_time_str) = &event.end_time {
         if !end_time_str.trim().is_empty() {
-            parse_event_time(end_date, end_time_str)
+            match parse_event_time(end_date, end_time_str, time_format)
                 .context(format!("Failed to parse end time: {}", end_time_str))?
+            {
+                EventTime::AllDay(date) if !start.is_all_day() => EventTime::Specific {
+                    date,
+                    time: END_OF_DAY,
+                },
+                end => end,
+            }
         } else {
             // If end time is empty, use end of day or match start
             if start.is_all_day() {
@@ -50,28 +492,104 @@ fn parse_event(event: CalendarEventXml) -> Result<CalendarEvent> {
         }
     };
 
-    // Parse categories - comma-separated
-    let categories: Vec<String> = event
-        .category
-        .split(',')
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
-        .collect();
+    Ok((start, end))
+}
 
-    Ok(CalendarEvent {
-        event_id: event.event_id,
-        title: event.title,
-        description: event.description,
-        location: event.location,
-        categories,
-        start,
-        end,
-    })
+/// Where a timed event with an "All Day" end time is taken to finish
+const END_OF_DAY: NaiveTime = NaiveTime::from_hms_opt(23, 59, 0).unwrap();
+
+/// Parse a last-modified timestamp, either "10/12/2025 14:30[:00]" or ISO 8601
+fn parse_modified(value: &str) -> Option<NaiveDateTime> {
+    let value = value.trim();
+    if let Ok(modified) = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f") {
+        return Some(modified);
+    }
+
+    let (date, time) = value.split_once(' ')?;
+    let date = parse_date(date).ok()?;
+    let time = NaiveTime::parse_from_str(time.trim(), "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(time.trim(), "%H:%M"))
+        .ok()?;
+    Some(date.and_time(time))
 }
 
+/// Keep a link only if it is a well-formed http(s) URL with a host
+fn parse_url(value: &str) -> Option<String> {
+    let value = value.trim();
+    let url = url::Url::parse(value).ok()?;
+    (matches!(url.scheme(), "http" | "https") && url.host().is_some()).then(|| value.to_string())
+}
+
+/// Work out an event's status from its `Status` text and `Cancelled` flag
+///
+/// A true `Cancelled` flag wins; otherwise unrecognised or missing statuses
+/// count as confirmed.
+fn parse_status(status: Option<&str>, cancelled: Option<&str>) -> EventStatus {
+    if cancelled.and_then(parse_bool) == Some(true) {
+        return EventStatus::Cancelled;
+    }
+    match status.map(|s| s.trim().to_ascii_lowercase()).as_deref() {
+        Some("cancelled" | "canceled") => EventStatus::Cancelled,
+        Some("tentative" | "provisional") => EventStatus::Tentative,
+        _ => EventStatus::Confirmed,
+    }
+}
+
+/// Parse a priority given as a word or as an Outlook-style importance number
+/// (0 = low, 1 = normal, 2 = high), returning `None` if unrecognised
+fn parse_priority(value: &str) -> Option<Priority> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "high" | "important" | "urgent" | "2" => Some(Priority::High),
+        "normal" | "medium" | "1" => Some(Priority::Normal),
+        "low" | "0" => Some(Priority::Low),
+        _ => None,
+    }
+}
+
+/// Parse a boolean flag such as "true", "False" or "1", returning `None` if unrecognised
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "1" | "yes" => Some(true),
+        "false" | "0" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// Separators accepted between the day, month and year components of a date
+const DATE_SEPARATORS: [char; 3] = ['/', '-', '.'];
+
+/// Years a real feed's dates can fall in
+const PLAUSIBLE_YEARS: RangeInclusive<i32> = 1000..=9999;
+
 /// Parse date in format "10/12/2025" (DD/MM/YYYY)
+///
+/// `-` and `.` are also accepted as separators (e.g. "10-12-2025" or "10.12.2025"),
+/// but a single date must use the same separator throughout. Two-digit years
+/// ("10/12/25") read as 1970-2069, as they do in the textual "10 Dec 25" form
+/// used for requests, which is accepted too.
 fn parse_date(date_str: &str) -> Result<NaiveDate> {
-    let parts: Vec<&str> = date_str.split('/').collect();
+    let date = parse_date_unchecked(date_str)?;
+    // chrono can hold years like 0 or 99999, but a school calendar never
+    // does, so treat them as corrupt rather than letting them through
+    if !PLAUSIBLE_YEARS.contains(&date.year()) {
+        anyhow::bail!("Year out of range: {}", date_str);
+    }
+    Ok(date)
+}
+
+fn parse_date_unchecked(date_str: &str) -> Result<NaiveDate> {
+    let Some(separator) = date_str.chars().find(|c| DATE_SEPARATORS.contains(c)) else {
+        return parse_textual_date(date_str);
+    };
+
+    if date_str
+        .chars()
+        .any(|c| c != separator && DATE_SEPARATORS.contains(&c))
+    {
+        anyhow::bail!("Mixed date separators: {}", date_str);
+    }
+
+    let parts: Vec<&str> = date_str.split(separator).collect();
 
     if parts.len() != 3 {
         anyhow::bail!("Invalid date format: {}", date_str);
@@ -86,20 +604,42 @@ fn parse_date(date_str: &str) -> Result<NaiveDate> {
     let year: i32 = parts[2]
         .parse()
         .context(format!("Invalid year: {}", parts[2]))?;
+    // Match chrono's %y, which the textual form is parsed with
+    let year = match (parts[2].len(), year) {
+        (2, 0..70) => 2000 + year,
+        (2, _) => 1900 + year,
+        _ => year,
+    };
 
     NaiveDate::from_ymd_opt(year, month, day)
         .context(format!("Invalid date: {}/{}/{}", day, month, year))
 }
 
-/// Parse event time - can be "All Day" or "HH:MM" format
-fn parse_event_time(date: NaiveDate, time_str: &str) -> Result<EventTime> {
+/// Parse a date in "DD MMM YYYY" or "DD MMM YY" format, ignoring the month's case
+fn parse_textual_date(date_str: &str) -> Result<NaiveDate> {
+    let parts: Vec<&str> = date_str.split_whitespace().collect();
+    let [day, month, year] = parts[..] else {
+        anyhow::bail!("Invalid date format: {}", date_str);
+    };
+    // %Y would happily read "25" as the year 25, so pick the format by length
+    let format = if year.len() == 2 {
+        "%d %b %y"
+    } else {
+        "%d %b %Y"
+    };
+    NaiveDate::parse_from_str(&format!("{} {} {}", day, month, year), format)
+        .context(format!("Invalid date: {}", date_str))
+}
+
+/// Parse event time - can be "All Day" or a time in `format`, "HH:MM" by default
+fn parse_event_time(date: NaiveDate, time_str: &str, format: Option<&str>) -> Result<EventTime> {
     let time_str = time_str.trim();
 
     if time_str.eq_ignore_ascii_case("all day") || time_str.is_empty() {
         return Ok(EventTime::AllDay(date));
     }
 
-    let time = NaiveTime::parse_from_str(time_str, "%H:%M")
+    let time = NaiveTime::parse_from_str(time_str, format.unwrap_or("%H:%M"))
         .context(format!("Failed to parse time: {}", time_str))?;
 
     Ok(EventTime::Specific { date, time })
@@ -118,17 +658,45 @@ mod tests {
         assert_eq!(date.year(), 2025);
     }
 
+    #[test]
+    fn test_parse_date_with_dashes() {
+        let date = parse_date("10-12-2025").unwrap();
+        assert_eq!(date, NaiveDate::from_ymd_opt(2025, 12, 10).unwrap());
+    }
+
+    #[test]
+    fn test_parse_date_with_dots() {
+        let date = parse_date("10.12.2025").unwrap();
+        assert_eq!(date, NaiveDate::from_ymd_opt(2025, 12, 10).unwrap());
+    }
+
+    #[test]
+    fn test_parse_date_mixed_separators() {
+        assert!(parse_date("10/12-2025").is_err());
+        assert!(parse_date("10.12/2025").is_err());
+    }
+
+    #[test]
+    fn test_parse_textual_dates() {
+        let expected = NaiveDate::from_ymd_opt(2025, 12, 10).unwrap();
+        assert_eq!(parse_date("10 Dec 2025").unwrap(), expected);
+        assert_eq!(parse_date("10 dec 25").unwrap(), expected);
+        assert_eq!(parse_date("10 DEC 2025").unwrap(), expected);
+        assert!(parse_date("10 Decembre 2025").is_err());
+        assert!(parse_date("10 Dec").is_err());
+    }
+
     #[test]
     fn test_parse_all_day_time() {
         let date = NaiveDate::from_ymd_opt(2025, 12, 10).unwrap();
-        let event_time = parse_event_time(date, "All Day").unwrap();
+        let event_time = parse_event_time(date, "All Day", None).unwrap();
         assert!(event_time.is_all_day());
     }
 
     #[test]
     fn test_parse_specific_time() {
         let date = NaiveDate::from_ymd_opt(2025, 12, 10).unwrap();
-        let event_time = parse_event_time(date, "08:30").unwrap();
+        let event_time = parse_event_time(date, "08:30", None).unwrap();
         assert!(!event_time.is_all_day());
 
         if let EventTime::Specific { time, .. } = event_time {
@@ -138,4 +706,611 @@ mod tests {
             panic!("Expected specific time");
         }
     }
+
+    #[test]
+    fn test_lenient_keeps_event_with_missing_location() {
+        let xml = "<SOCSCalendar><CalendarEvent>\
+             <EventID>7</EventID>\
+             <StartDate>10/12/2025</StartDate><EndDate>10/12/2025</EndDate>\
+             <StartTime>08:30</StartTime><EndTime>09:30</EndTime>\
+             <Title>Chapel</Title><Category>Whole School</Category>\
+             </CalendarEvent></SOCSCalendar>";
+
+        assert!(parse_calendar_xml(xml.to_string()).is_err());
+
+        let options = ParseOptions {
+            mode: ParseMode::Lenient,
+            ..Default::default()
+        };
+        let parsed = parse_calendar_xml_with_options(xml, &options).unwrap();
+        assert_eq!(parsed.events.len(), 1);
+        assert_eq!(parsed.events[0].title, "Chapel");
+        assert_eq!(parsed.events[0].location, "");
+        assert_eq!(parsed.diagnostics.len(), 1);
+        assert_eq!(parsed.diagnostics[0].event_id, "7");
+    }
+
+    #[test]
+    fn test_bad_events_strict_and_lenient() {
+        let xml = "<SOCSCalendar>\
+             <CalendarEvent><EventID>1</EventID>\
+             <StartDate>32/12/2025</StartDate><EndDate>32/12/2025</EndDate>\
+             <StartTime>08:30</StartTime><Title>Bad date</Title>\
+             <Location></Location><Category></Category></CalendarEvent>\
+             <CalendarEvent><EventID>2</EventID>\
+             <StartDate>10/12/2025</StartDate><EndDate>10/12/2025</EndDate>\
+             <StartTime>08:30</StartTime><Title>Good</Title>\
+             <Location></Location><Category></Category></CalendarEvent>\
+             <CalendarEvent><EventID>3</EventID>\
+             <StartDate>10/12/2025</StartDate><EndDate>10/12/2025</EndDate>\
+             <StartTime>25:99</StartTime><Title>Bad time</Title>\
+             <Location></Location><Category></Category></CalendarEvent>\
+             </SOCSCalendar>";
+
+        let error = parse_calendar_xml(xml.to_string()).unwrap_err();
+        assert!(format!("{:#}", error).contains("32/12/2025"));
+
+        let options = ParseOptions {
+            mode: ParseMode::Lenient,
+            ..Default::default()
+        };
+        let parsed = parse_calendar_xml_with_options(xml, &options).unwrap();
+        assert_eq!(parsed.events.len(), 1);
+        assert_eq!(parsed.events[0].event_id, "2");
+        let failed: Vec<&str> = parsed.errors.iter().map(|e| e.event_id.as_str()).collect();
+        assert_eq!(failed, vec!["1", "3"]);
+        assert!(parsed.errors[1].message.contains("25:99"));
+    }
+
+    #[test]
+    fn test_parse_date_two_digit_year() {
+        assert_eq!(
+            parse_date("10/12/25").unwrap(),
+            NaiveDate::from_ymd_opt(2025, 12, 10).unwrap()
+        );
+        assert_eq!(
+            parse_date("01.09.99").unwrap(),
+            NaiveDate::from_ymd_opt(1999, 9, 1).unwrap()
+        );
+        assert_eq!(
+            parse_date("10/12/25").unwrap(),
+            parse_date("10 Dec 25").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_out_of_range_years() {
+        let xml = "<SOCSCalendar>\
+             <CalendarEvent><EventID>1</EventID>\
+             <StartDate>10/12/0</StartDate><EndDate>10/12/0</EndDate>\
+             <StartTime>08:30</StartTime><Title>Year zero</Title>\
+             <Location></Location><Category></Category></CalendarEvent>\
+             <CalendarEvent><EventID>2</EventID>\
+             <StartDate>10/12/2025</StartDate><EndDate>10/12/99999</EndDate>\
+             <StartTime>08:30</StartTime><Title>Far future</Title>\
+             <Location></Location><Category></Category></CalendarEvent>\
+             <CalendarEvent><EventID>3</EventID>\
+             <StartDate>10/12/2025</StartDate><EndDate>10/12/2025</EndDate>\
+             <StartTime>08:30</StartTime><Title>Good</Title>\
+             <Location></Location><Category></Category></CalendarEvent>\
+             </SOCSCalendar>";
+
+        assert!(parse_calendar_xml(xml.to_string()).is_err());
+
+        let options = ParseOptions {
+            mode: ParseMode::Lenient,
+            ..Default::default()
+        };
+        let parsed = parse_calendar_xml_with_options(xml, &options).unwrap();
+        assert_eq!(parsed.events.len(), 1);
+        assert_eq!(parsed.events[0].event_id, "3");
+        let failed: Vec<&str> = parsed.errors.iter().map(|e| e.event_id.as_str()).collect();
+        assert_eq!(failed, vec!["1", "2"]);
+        assert!(parsed.errors[0].message.contains("Year out of range"));
+        assert_eq!(parsed.diagnostics_at_least(Severity::Error).count(), 2);
+    }
+
+    #[test]
+    fn test_diagnostic_severity() {
+        let xml = "<SOCSCalendar>\
+             <CalendarEvent><EventID>1</EventID>\
+             <StartDate>10/12/2025</StartDate><EndDate>10/12/2025</EndDate>\
+             <StartTime>08:30</StartTime><Title></Title>\
+             <Location></Location><Category></Category></CalendarEvent>\
+             <CalendarEvent><EventID>2</EventID>\
+             <StartDate>10/13/2025</StartDate><EndDate>10/13/2025</EndDate>\
+             <StartTime>08:30</StartTime><Title>Bad date</Title>\
+             <Location></Location><Category></Category></CalendarEvent>\
+             </SOCSCalendar>";
+        let options = ParseOptions {
+            mode: ParseMode::Lenient,
+            ..Default::default()
+        };
+
+        let parsed = parse_calendar_xml_with_options(xml, &options).unwrap();
+        let severities: Vec<(&str, Severity)> = parsed
+            .diagnostics
+            .iter()
+            .map(|d| (d.event_id.as_str(), d.severity))
+            .collect();
+        assert_eq!(
+            severities,
+            vec![("1", Severity::Warning), ("2", Severity::Error)]
+        );
+
+        let errors: Vec<&ParseDiagnostic> = parsed.diagnostics_at_least(Severity::Error).collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].event_id, "2");
+    }
+
+    #[test]
+    fn test_sort_categories_option() {
+        let xml = "<SOCSCalendar><CalendarEvent>\
+             <EventID>1</EventID>\
+             <StartDate>10/12/2025</StartDate><EndDate>10/12/2025</EndDate>\
+             <StartTime>14:00</StartTime><EndTime>16:00</EndTime>\
+             <Title>Rugby v St Olave's</Title><Location>Astro</Location>\
+             <Category>U15, rugby, Away, Rugby</Category>\
+             </CalendarEvent></SOCSCalendar>";
+
+        let unsorted = parse_calendar_xml(xml.to_string()).unwrap();
+        assert_eq!(
+            unsorted[0].categories,
+            vec!["U15", "rugby", "Away", "Rugby"]
+        );
+
+        let options = ParseOptions {
+            sort_categories: true,
+            ..Default::default()
+        };
+        let sorted = parse_calendar_xml_with_options(xml, &options).unwrap();
+        assert_eq!(sorted.events[0].categories, vec!["Away", "rugby", "U15"]);
+    }
+
+    #[test]
+    fn test_parse_calendar_xml_at_nested_root() {
+        let xml = "<Integration><School name=\"Example\">\
+             <SOCSCalendar>\
+             <CalendarEvent>\
+             <EventID>1</EventID>\
+             <StartDate>10/12/2025</StartDate><EndDate>10/12/2025</EndDate>\
+             <StartTime>08:30</StartTime><EndTime>09:30</EndTime>\
+             <Title>Chapel</Title><Location>Chapel</Location><Category>Whole School</Category>\
+             </CalendarEvent>\
+             </SOCSCalendar>\
+             </School><Generated>2025-12-01</Generated></Integration>";
+
+        let events = parse_calendar_xml_at(xml, "SOCSCalendar").unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].title, "Chapel");
+
+        assert!(parse_calendar_xml_at(xml, "SOCS").is_err());
+    }
+
+    #[test]
+    fn test_parse_collapses_duplicate_events() {
+        let event = |title: &str| {
+            format!(
+                "<CalendarEvent>\
+                 <EventID>5</EventID>\
+                 <StartDate>10/12/2025</StartDate><EndDate>10/12/2025</EndDate>\
+                 <StartTime>14:00</StartTime><EndTime>16:00</EndTime>\
+                 <Title>{}</Title><Location>Astro</Location><Category>Sport</Category>\
+                 </CalendarEvent>",
+                title
+            )
+        };
+        let (short, long) = (
+            event("Hockey v Eton"),
+            event("Hockey v Eton (Co-Curricular)"),
+        );
+
+        for xml in [
+            format!("<SOCSCalendar>{}{}</SOCSCalendar>", short, long),
+            format!("<SOCSCalendar>{}{}</SOCSCalendar>", long, short),
+        ] {
+            let events = parse_calendar_xml(xml).unwrap();
+            assert_eq!(events.len(), 1);
+            assert_eq!(events[0].title, "Hockey v Eton");
+        }
+    }
+
+    #[test]
+    fn test_looks_truncated() {
+        use crate::test_utils::all_day;
+
+        let start = NaiveDate::from_ymd_opt(2025, 12, 1).unwrap();
+        let end = NaiveDate::from_ymd_opt(2025, 12, 31).unwrap();
+        let busy: Vec<CalendarEvent> = (0..60)
+            .map(|i| all_day(&i.to_string(), start + chrono::Days::new(i / 6)))
+            .collect();
+        assert!(looks_truncated(&busy, end));
+
+        let mut complete = busy.clone();
+        complete.push(all_day(
+            "60",
+            NaiveDate::from_ymd_opt(2025, 12, 29).unwrap(),
+        ));
+        assert!(!looks_truncated(&complete, end));
+        assert!(!looks_truncated(&busy[..10], end));
+
+        let meta = CalendarMeta {
+            requested_start: Some(start),
+            requested_end: Some(end),
+            total_count: Some(12),
+        };
+        assert!(looks_truncated_with_meta(&busy[..10], end, Some(&meta)));
+        assert!(!looks_truncated_with_meta(&busy[..12], end, Some(&meta)));
+    }
+
+    #[test]
+    fn test_parse_header_meta() {
+        let plain = event_xml("08:30", "");
+        let xml = plain.replacen(
+            "<SOCSCalendar>",
+            r#"<SOCSCalendar><Header StartDate="01/12/2025" EndDate="31/12/2025" TotalCount="42"/>"#,
+            1,
+        );
+
+        let (events, meta) = parse_calendar_xml_with_meta(&xml).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            meta,
+            Some(CalendarMeta {
+                requested_start: NaiveDate::from_ymd_opt(2025, 12, 1),
+                requested_end: NaiveDate::from_ymd_opt(2025, 12, 31),
+                total_count: Some(42),
+            })
+        );
+
+        let count_only = plain.replacen(
+            "<SOCSCalendar>",
+            r#"<SOCSCalendar><Header TotalCount="5"/>"#,
+            1,
+        );
+        let (events, meta) = parse_calendar_xml_with_meta(&count_only).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            meta,
+            Some(CalendarMeta {
+                requested_start: None,
+                requested_end: None,
+                total_count: Some(5),
+            })
+        );
+
+        let (_, meta) = parse_calendar_xml_with_meta(&plain).unwrap();
+        assert_eq!(meta, None);
+    }
+
+    #[test]
+    fn test_parse_calendar_file_plain_and_gzip() {
+        use flate2::{Compression, write::GzEncoder};
+        use std::io::Write;
+
+        let xml = event_xml("08:30", "");
+        let dir = std::env::temp_dir().join(format!("socs-parse-file-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let plain = dir.join("cached.xml");
+        std::fs::write(&plain, &xml).unwrap();
+
+        let gzipped = dir.join("cached.xml.gz");
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(xml.as_bytes()).unwrap();
+        std::fs::write(&gzipped, encoder.finish().unwrap()).unwrap();
+
+        let from_plain = parse_calendar_file(&plain).unwrap();
+        let from_gzip = parse_calendar_file(&gzipped).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(from_plain.len(), 1);
+        assert_eq!(from_plain, from_gzip);
+        assert!(parse_calendar_file(dir.join("missing.xml")).is_err());
+    }
+
+    #[test]
+    fn test_parse_calendar_auto_json_envelope() {
+        let xml = event_xml("08:30", "");
+        let body = serde_json::json!({ "status": "ok", "data": xml }).to_string();
+
+        let events = parse_calendar_auto(&body).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events, parse_calendar_auto(&xml).unwrap());
+
+        assert!(parse_calendar_auto(r#"{"data": 42}"#).is_err());
+    }
+
+    #[test]
+    fn test_attribute_style_events() {
+        let attributes = r#"<SOCSCalendar><CalendarEvent EventID="1"
+            StartDate="10/12/2025" EndDate="10/12/2025" StartTime="08:30" EndTime=""
+            Title="Founders Day" Location="Chapel" Category="Whole School"/></SOCSCalendar>"#;
+
+        assert_eq!(
+            parse_calendar_xml(attributes.to_string()).unwrap(),
+            parse_calendar_xml(event_xml("08:30", "")).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_custom_time_format() {
+        let xml = "<SOCSCalendar><CalendarEvent>\
+            <EventID>1</EventID>\
+            <StartDate>10/12/2025</StartDate><EndDate>10/12/2025</EndDate>\
+            <StartTime>8.30am</StartTime><EndTime>2.15PM</EndTime>\
+            <Title>Sports Day</Title><Location></Location><Category></Category>\
+            </CalendarEvent></SOCSCalendar>";
+        assert!(parse_calendar_xml(xml.to_string()).is_err());
+
+        let options = ParseOptions {
+            time_format: Some("%I.%M%p".to_string()),
+            ..Default::default()
+        };
+        let parsed = parse_calendar_xml_with_options(xml, &options).unwrap();
+        let day = NaiveDate::from_ymd_opt(2025, 12, 10).unwrap();
+        assert_eq!(
+            parsed.events[0].start,
+            EventTime::Specific {
+                date: day,
+                time: NaiveTime::from_hms_opt(8, 30, 0).unwrap(),
+            }
+        );
+        assert_eq!(
+            parsed.events[0].end,
+            EventTime::Specific {
+                date: day,
+                time: NaiveTime::from_hms_opt(14, 15, 0).unwrap(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_timezone() {
+        let parse = |extra: &str| parse_calendar_xml(event_xml("08:30", extra)).unwrap();
+        let timezone = |extra: &str| parse(extra)[0].timezone;
+
+        let london = parse("<TimeZone>Europe/London</TimeZone>");
+        assert_eq!(london[0].timezone, Some(Tz::Europe__London));
+        assert_eq!(
+            london[0].start_utc(Tz::Asia__Tokyo).to_rfc3339(),
+            "2025-12-10T08:30:00+00:00"
+        );
+        assert_eq!(timezone("<TimeZone>Mars/Olympus_Mons</TimeZone>"), None);
+        assert_eq!(timezone(""), None);
+    }
+
+    #[test]
+    fn test_parse_house() {
+        let group = |extra: &str| {
+            parse_calendar_xml(event_xml("08:30", extra)).unwrap()[0]
+                .group
+                .clone()
+        };
+
+        assert_eq!(
+            group("<House> Wellington </House>").as_deref(),
+            Some("Wellington")
+        );
+        assert_eq!(group("<Team>1st XV</Team>").as_deref(), Some("1st XV"));
+        assert_eq!(group("<House></House>"), None);
+        assert_eq!(group(""), None);
+    }
+
+    #[test]
+    fn test_parse_status() {
+        let status = |extra: &str| parse_calendar_xml(event_xml("08:30", extra)).unwrap()[0].status;
+
+        assert_eq!(status(""), EventStatus::Confirmed);
+        assert_eq!(status("<Status>Cancelled</Status>"), EventStatus::Cancelled);
+        assert_eq!(
+            status("<Cancelled>true</Cancelled>"),
+            EventStatus::Cancelled
+        );
+        assert_eq!(
+            status("<Status>Provisional</Status>"),
+            EventStatus::Tentative
+        );
+        assert_eq!(
+            status("<Cancelled>false</Cancelled>"),
+            EventStatus::Confirmed
+        );
+    }
+
+    #[test]
+    fn test_parse_url() {
+        let url = |extra: &str| {
+            parse_calendar_xml(event_xml("08:30", extra)).unwrap()[0]
+                .url
+                .clone()
+        };
+
+        assert_eq!(
+            url("<URL> https://www.socscms.com/socs/event.aspx?id=1 </URL>").as_deref(),
+            Some("https://www.socscms.com/socs/event.aspx?id=1")
+        );
+        assert_eq!(
+            url("<Link>http://example.com/fixtures</Link>").as_deref(),
+            Some("http://example.com/fixtures")
+        );
+        assert_eq!(url("<URL>javascript:alert(1)</URL>"), None);
+        assert_eq!(url("<URL>not a url</URL>"), None);
+        assert_eq!(url(""), None);
+    }
+
+    #[test]
+    fn test_keep_raw_fields() {
+        let xml = event_xml("8:30", "");
+        assert_eq!(parse_calendar_xml(xml.clone()).unwrap()[0].raw, None);
+
+        let options = ParseOptions {
+            keep_raw: true,
+            ..Default::default()
+        };
+        let parsed = parse_calendar_xml_with_options(&xml, &options).unwrap();
+        assert_eq!(
+            parsed.events[0].raw,
+            Some(RawFields {
+                start_date: "10/12/2025".to_string(),
+                end_date: "10/12/2025".to_string(),
+                start_time: "8:30".to_string(),
+                end_time: Some(String::new()),
+                all_day_event: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_timed_start_with_all_day_end() {
+        let xml = "<SOCSCalendar><CalendarEvent>\
+            <EventID>1</EventID>\
+            <StartDate>10/12/2025</StartDate><EndDate>10/12/2025</EndDate>\
+            <StartTime>09:00</StartTime><EndTime>All Day</EndTime>\
+            <Title>Open Day</Title><Location></Location><Category></Category>\
+            </CalendarEvent></SOCSCalendar>";
+
+        let events = parse_calendar_xml(xml.to_string()).unwrap();
+        let day = NaiveDate::from_ymd_opt(2025, 12, 10).unwrap();
+        assert_eq!(
+            events[0].start,
+            EventTime::Specific {
+                date: day,
+                time: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            }
+        );
+        assert_eq!(
+            events[0].end,
+            EventTime::Specific {
+                date: day,
+                time: NaiveTime::from_hms_opt(23, 59, 0).unwrap(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_empty_end_date() {
+        let xml = "<SOCSCalendar><CalendarEvent>\
+            <EventID>1</EventID>\
+            <StartDate>10/12/2025</StartDate><EndDate></EndDate>\
+            <StartTime></StartTime>\
+            <Title>Staff Training</Title><Location></Location><Category></Category>\
+            </CalendarEvent></SOCSCalendar>"
+            .to_string();
+
+        let events = parse_calendar_xml(xml).unwrap();
+        let day = NaiveDate::from_ymd_opt(2025, 12, 10).unwrap();
+        assert_eq!(events[0].start, EventTime::AllDay(day));
+        assert_eq!(events[0].end, EventTime::AllDay(day));
+    }
+
+    #[test]
+    fn test_parse_priority() {
+        let xml = event_xml("08:30", "<Priority>High</Priority>");
+        assert_eq!(
+            parse_calendar_xml(xml).unwrap()[0].priority,
+            Some(Priority::High)
+        );
+
+        let xml = event_xml("08:30", "<Importance>0</Importance>");
+        assert_eq!(
+            parse_calendar_xml(xml).unwrap()[0].priority,
+            Some(Priority::Low)
+        );
+
+        let xml = event_xml("08:30", "");
+        assert_eq!(parse_calendar_xml(xml).unwrap()[0].priority, None);
+    }
+
+    #[test]
+    fn test_parse_modified_timestamp() {
+        let xml = event_xml("08:30", "<Modified>09/12/2025 16:45:10</Modified>");
+        let events = parse_calendar_xml(xml).unwrap();
+        assert_eq!(
+            events[0].modified,
+            NaiveDate::from_ymd_opt(2025, 12, 9)
+                .unwrap()
+                .and_hms_opt(16, 45, 10)
+        );
+
+        let xml = event_xml("08:30", "<LastUpdated>2025-12-09T16:45:10</LastUpdated>");
+        let events = parse_calendar_xml(xml).unwrap();
+        assert!(events[0].modified.is_some());
+
+        let xml = event_xml("08:30", "<Modified>last Tuesday</Modified>");
+        let events = parse_calendar_xml(xml).unwrap();
+        assert_eq!(events[0].modified, None);
+
+        let xml = event_xml("08:30", "");
+        let events = parse_calendar_xml(xml).unwrap();
+        assert_eq!(events[0].modified, None);
+    }
+
+    #[test]
+    fn test_parse_alternate_element_names() {
+        let xml = "<Events>\
+             <Event>\
+             <EventID>1</EventID>\
+             <StartDate>10/12/2025</StartDate><EndDate>10/12/2025</EndDate>\
+             <StartTime>08:30</StartTime><EndTime>09:30</EndTime>\
+             <Title>Chapel</Title><Location>Chapel</Location><Category>Whole School</Category>\
+             </Event>\
+             <Event>\
+             <EventID>2</EventID>\
+             <StartDate>11/12/2025</StartDate><EndDate>11/12/2025</EndDate>\
+             <StartTime>All Day</StartTime>\
+             <Title>INSET</Title><Location></Location><Category>Staff</Category>\
+             </Event>\
+             </Events>";
+
+        let events = parse_calendar_xml(xml.to_string()).unwrap();
+        let ids: Vec<&str> = events.iter().map(|e| e.event_id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "2"]);
+    }
+
+    fn event_xml(start_time: &str, extra_xml: &str) -> String {
+        format!(
+            "<SOCSCalendar><CalendarEvent>\
+             <EventID>1</EventID>\
+             <StartDate>10/12/2025</StartDate><EndDate>10/12/2025</EndDate>\
+             <StartTime>{}</StartTime><EndTime></EndTime>\
+             <Title>Founders Day</Title><Location>Chapel</Location><Category>Whole School</Category>\
+             {}\
+             </CalendarEvent></SOCSCalendar>",
+            start_time, extra_xml
+        )
+    }
+
+    #[test]
+    fn test_all_day_event_flag_true_overrides_time() {
+        let xml = event_xml("00:00", "<AllDayEvent>true</AllDayEvent>");
+        let events = parse_calendar_xml(xml).unwrap();
+        let date = NaiveDate::from_ymd_opt(2025, 12, 10).unwrap();
+        assert_eq!(events[0].start, EventTime::AllDay(date));
+        assert_eq!(events[0].end, EventTime::AllDay(date));
+    }
+
+    #[test]
+    fn test_all_day_event_flag_false_keeps_time() {
+        let xml = event_xml("00:00", "<AllDayEvent>false</AllDayEvent>");
+        let events = parse_calendar_xml(xml).unwrap();
+        assert!(!events[0].start.is_all_day());
+
+        let xml = event_xml("All Day", "<AllDayEvent>false</AllDayEvent>");
+        let events = parse_calendar_xml(xml).unwrap();
+        let date = NaiveDate::from_ymd_opt(2025, 12, 10).unwrap();
+        assert_eq!(
+            events[0].start,
+            EventTime::Specific {
+                date,
+                time: NaiveTime::MIN
+            }
+        );
+        assert_eq!(
+            events[0].end,
+            EventTime::Specific {
+                date,
+                time: END_OF_DAY
+            }
+        );
+    }
 }
@@ -0,0 +1,370 @@
+use crate::models::{CalendarEvent, EventTime};
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, BinaryHeap};
+
+/// Find the unbooked intervals in a location on a given day
+///
+/// Bookings in `location` (matched case-insensitively) are clipped to the
+/// `open`..`close` window and merged where they overlap or touch, and the gaps
+/// between them are returned in order. An all-day event in the location books
+/// it out for the whole day, so no free slots are returned.
+pub fn free_slots(
+    events: &[CalendarEvent],
+    location: &str,
+    day: NaiveDate,
+    open: NaiveTime,
+    close: NaiveTime,
+) -> Vec<(NaiveTime, NaiveTime)> {
+    let mut bookings = Vec::new();
+
+    for event in events {
+        if !event.location.trim().eq_ignore_ascii_case(location.trim()) {
+            continue;
+        }
+        if event.start.date() > day || event.end.date() < day {
+            continue;
+        }
+        if event.start.is_all_day() {
+            return Vec::new();
+        }
+
+        let start = match &event.start {
+            EventTime::Specific { date, time } if *date == day => (*time).max(open),
+            _ => open,
+        };
+        let end = match &event.end {
+            EventTime::Specific { date, time } if *date == day => (*time).min(close),
+            _ => close,
+        };
+
+        if start < end {
+            bookings.push((start, end));
+        }
+    }
+
+    bookings.sort();
+
+    let mut slots = Vec::new();
+    let mut cursor = open;
+    for (start, end) in bookings {
+        if start > cursor {
+            slots.push((cursor, start));
+        }
+        cursor = cursor.max(end);
+    }
+    if cursor < close {
+        slots.push((cursor, close));
+    }
+
+    slots
+}
+
+/// Give each event a lane so overlapping events can be drawn side by side
+///
+/// Timed events are placed greedily in start order, each taking the lowest
+/// lane that is free when it starts, which uses the fewest lanes possible.
+/// Events that merely touch (one ends as the next starts) can share a lane.
+/// All-day events don't sit on the time grid, so they all share lane 0 of
+/// their own all-day row. The result is in chronological order.
+pub fn assign_lanes(events: &[CalendarEvent]) -> Vec<(usize, &CalendarEvent)> {
+    let mut sorted: Vec<&CalendarEvent> = events.iter().collect();
+    sorted.sort_by(|a, b| a.chronological_cmp(b));
+
+    let mut free: BinaryHeap<Reverse<usize>> = BinaryHeap::new();
+    let mut busy: BinaryHeap<Reverse<(NaiveDateTime, usize)>> = BinaryHeap::new();
+    let mut lane_count = 0;
+
+    let mut lanes = Vec::with_capacity(sorted.len());
+    for event in sorted {
+        if event.start.is_all_day() {
+            lanes.push((0, event));
+            continue;
+        }
+        let (start, end) = event.local_span();
+
+        while let Some(&Reverse((busy_until, lane))) = busy.peek() {
+            if busy_until > start {
+                break;
+            }
+            busy.pop();
+            free.push(Reverse(lane));
+        }
+
+        let lane = free.pop().map(|Reverse(lane)| lane).unwrap_or_else(|| {
+            lane_count += 1;
+            lane_count - 1
+        });
+        busy.push(Reverse((end, lane)));
+        lanes.push((lane, event));
+    }
+    lanes
+}
+
+/// How long two events overlap, or `None` if they don't
+///
+/// All-day events span midnight to midnight, so they can be compared with
+/// timed ones. Events that only touch, one ending as the other starts, don't
+/// overlap.
+pub fn overlap(a: &CalendarEvent, b: &CalendarEvent) -> Option<Duration> {
+    let (a_start, a_end) = a.local_span();
+    let (b_start, b_end) = b.local_span();
+    let overlap = a_end.min(b_end) - a_start.max(b_start);
+    (overlap > Duration::zero()).then_some(overlap)
+}
+
+/// How long until the next timed event starts after `now`, and which event it is
+///
+/// Events that have already started, including one starting exactly at
+/// `now`, are ignored, as are all-day events, which have no start time to
+/// count down to. Ties go to the event that sorts first chronologically.
+pub fn time_until_next(
+    events: &[CalendarEvent],
+    now: NaiveDateTime,
+) -> Option<(Duration, &CalendarEvent)> {
+    events
+        .iter()
+        .filter(|event| !event.start.is_all_day())
+        .map(|event| (event.local_span().0 - now, event))
+        .filter(|(until, _)| *until > Duration::zero())
+        .min_by(|(a, a_event), (b, b_event)| {
+            a.cmp(b).then_with(|| a_event.chronological_cmp(b_event))
+        })
+}
+
+/// Stretches of `start..=end` with no events, longer than `max_gap`
+///
+/// A day counts as covered if any event runs over it, all-day or not. Each
+/// run of uncovered days lasting more than `max_gap` is returned as its
+/// first and last day. Real calendars have quiet spells, so this is only a
+/// hint that a fetch may have been truncated.
+pub fn coverage_gaps(
+    events: &[CalendarEvent],
+    start: NaiveDate,
+    end: NaiveDate,
+    max_gap: Duration,
+) -> Vec<(NaiveDate, NaiveDate)> {
+    let covered: BTreeSet<NaiveDate> = events
+        .iter()
+        .flat_map(|event| {
+            let first = event.start.date().max(start);
+            let last = event.end.date().max(event.start.date()).min(end);
+            first.iter_days().take_while(move |day| *day <= last)
+        })
+        .collect();
+
+    let mut gaps = Vec::new();
+    let mut gap_start = None;
+    for day in start.iter_days().take_while(|day| *day <= end) {
+        match (covered.contains(&day), gap_start) {
+            (false, None) => gap_start = Some(day),
+            (true, Some(first)) => {
+                gaps.push((first, day.pred_opt().unwrap_or(day)));
+                gap_start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(first) = gap_start {
+        gaps.push((first, end));
+    }
+
+    gaps.retain(|(first, last)| *last - *first + Duration::days(1) > max_gap);
+    gaps
+}
+
+/// Consecutive events at different locations with less than `min_gap` between them
+///
+/// Timed events are taken in chronological order and each is compared with
+/// the one after it; a pair is returned when their locations differ (ignoring
+/// case) and the second starts less than `min_gap` after the first ends,
+/// including when they overlap. All-day events and events without a
+/// location are left out, as there's no way to tell where or when they
+/// need you.
+pub fn tight_transitions(
+    events: &[CalendarEvent],
+    min_gap: Duration,
+) -> Vec<(CalendarEvent, CalendarEvent)> {
+    let mut timed: Vec<&CalendarEvent> = events
+        .iter()
+        .filter(|event| !event.start.is_all_day() && !event.location.trim().is_empty())
+        .collect();
+    timed.sort_by(|a, b| a.chronological_cmp(b));
+
+    timed
+        .windows(2)
+        .filter(|pair| {
+            let (first, second) = (pair[0], pair[1]);
+            let gap = second.local_span().0 - first.local_span().1;
+            gap < min_gap
+                && !first
+                    .location
+                    .trim()
+                    .eq_ignore_ascii_case(second.location.trim())
+        })
+        .map(|pair| (pair[0].clone(), pair[1].clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{all_day, date, event, time, timed};
+
+    fn booking(id: &str, day: NaiveDate, start: (u32, u32), end: (u32, u32)) -> CalendarEvent {
+        let mut event = event(id, timed(day, start.0, start.1), timed(day, end.0, end.1));
+        event.location = "Sports Hall".to_string();
+        event
+    }
+
+    #[test]
+    fn test_free_slots_back_to_back_and_gap() {
+        let day = date(2025, 12, 10);
+        let events = vec![
+            booking("1", day, (9, 0), (10, 0)),
+            booking("2", day, (10, 0), (11, 0)),
+            booking("3", day, (10, 30), (11, 30)),
+            booking("4", day, (14, 0), (15, 0)),
+        ];
+
+        let slots = free_slots(&events, "sports hall", day, time(8, 0), time(17, 0));
+        assert_eq!(
+            slots,
+            vec![
+                (time(8, 0), time(9, 0)),
+                (time(11, 30), time(14, 0)),
+                (time(15, 0), time(17, 0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_free_slots_all_day_booking() {
+        let day = date(2025, 12, 10);
+        let mut closed = all_day("1", day);
+        closed.location = "Sports Hall".to_string();
+        let events = vec![closed, booking("2", day, (9, 0), (10, 0))];
+
+        assert!(free_slots(&events, "Sports Hall", day, time(8, 0), time(17, 0)).is_empty());
+    }
+
+    fn lanes(events: &[CalendarEvent]) -> Vec<(usize, &str)> {
+        assign_lanes(events)
+            .into_iter()
+            .map(|(lane, event)| (lane, event.event_id.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_assign_lanes_two_overlapping() {
+        let day = date(2025, 12, 10);
+        let events = vec![
+            event("b", timed(day, 9, 30), timed(day, 10, 30)),
+            event("a", timed(day, 9, 0), timed(day, 10, 0)),
+            event("c", timed(day, 10, 30), timed(day, 11, 0)),
+            all_day("inset", day),
+        ];
+
+        assert_eq!(
+            lanes(&events),
+            vec![(0, "inset"), (0, "a"), (1, "b"), (0, "c")]
+        );
+    }
+
+    #[test]
+    fn test_assign_lanes_three_pairwise_overlapping() {
+        let day = date(2025, 12, 10);
+        let events = vec![
+            event("a", timed(day, 9, 0), timed(day, 12, 0)),
+            event("b", timed(day, 9, 30), timed(day, 11, 0)),
+            event("c", timed(day, 10, 0), timed(day, 10, 30)),
+        ];
+
+        assert_eq!(lanes(&events), vec![(0, "a"), (1, "b"), (2, "c")]);
+    }
+
+    #[test]
+    fn test_time_until_next() {
+        let day = date(2025, 12, 10);
+        let now = day.and_time(time(10, 45));
+        let events = vec![
+            event("1", timed(day, 9, 0), timed(day, 10, 0)),
+            event("2", timed(day, 10, 30), timed(day, 11, 30)),
+            event("3", timed(day, 14, 0), timed(day, 15, 0)),
+            event("4", timed(day, 11, 0), timed(day, 12, 0)),
+            all_day("5", date(2025, 12, 11)),
+        ];
+
+        let (until, next) = time_until_next(&events, now).unwrap();
+        assert_eq!(until, Duration::minutes(15));
+        assert_eq!(next.event_id, "4");
+
+        assert!(time_until_next(&events, day.and_time(time(14, 0))).is_none());
+    }
+
+    #[test]
+    fn test_coverage_gaps() {
+        let events = vec![
+            all_day("1", date(2025, 12, 1)),
+            event(
+                "2",
+                timed(date(2025, 12, 2), 18, 0),
+                timed(date(2025, 12, 3), 9, 0),
+            ),
+            all_day("3", date(2025, 12, 5)),
+            all_day("4", date(2025, 12, 20)),
+        ];
+
+        let gaps = coverage_gaps(
+            &events,
+            date(2025, 12, 1),
+            date(2025, 12, 31),
+            Duration::days(3),
+        );
+        assert_eq!(
+            gaps,
+            vec![
+                (date(2025, 12, 6), date(2025, 12, 19)),
+                (date(2025, 12, 21), date(2025, 12, 31)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tight_transitions() {
+        let day = date(2025, 12, 10);
+        let mut lesson = event("1", timed(day, 9, 0), timed(day, 10, 0));
+        lesson.location = "Science Block".to_string();
+        let mut rehearsal = event("2", timed(day, 10, 5), timed(day, 11, 0));
+        rehearsal.location = "Music School".to_string();
+        let mut practice = event("3", timed(day, 11, 5), timed(day, 12, 0));
+        practice.location = "music school".to_string();
+        let mut fixture = event("4", timed(day, 14, 0), timed(day, 16, 0));
+        fixture.location = "Eton College".to_string();
+        let events = vec![fixture, practice, rehearsal, lesson];
+
+        let tight = tight_transitions(&events, Duration::minutes(15));
+        let ids: Vec<(&str, &str)> = tight
+            .iter()
+            .map(|(a, b)| (a.event_id.as_str(), b.event_id.as_str()))
+            .collect();
+        assert_eq!(ids, vec![("1", "2")]);
+    }
+
+    #[test]
+    fn test_overlap() {
+        let day = date(2025, 12, 10);
+        let rehearsal = event("1", timed(day, 9, 0), timed(day, 11, 0));
+        let partial = event("2", timed(day, 10, 15), timed(day, 12, 0));
+        let contained = event("3", timed(day, 9, 30), timed(day, 10, 0));
+        let after = event("4", timed(day, 11, 0), timed(day, 12, 0));
+
+        assert_eq!(overlap(&rehearsal, &partial), Some(Duration::minutes(45)));
+        assert_eq!(overlap(&rehearsal, &contained), Some(Duration::minutes(30)));
+        assert_eq!(overlap(&rehearsal, &after), None);
+        assert_eq!(
+            overlap(&all_day("5", day), &rehearsal),
+            Some(Duration::hours(2))
+        );
+    }
+}
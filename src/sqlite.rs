@@ -0,0 +1,149 @@
+use crate::models::{CalendarEvent, EventTime};
+use anyhow::{Context, Result};
+use rusqlite::{Connection, params};
+
+const CREATE_TABLE: &str = "CREATE TABLE IF NOT EXISTS calendar_events (
+    event_id TEXT PRIMARY KEY,
+    title TEXT NOT NULL,
+    description TEXT,
+    location TEXT NOT NULL,
+    categories TEXT NOT NULL,
+    start_date TEXT NOT NULL,
+    start_time TEXT,
+    end_date TEXT NOT NULL,
+    end_time TEXT,
+    modified TEXT,
+    priority TEXT,
+    url TEXT,
+    status TEXT NOT NULL,
+    timezone TEXT,
+    group_name TEXT
+)";
+
+const UPSERT: &str = "INSERT INTO calendar_events (
+    event_id, title, description, location, categories,
+    start_date, start_time, end_date, end_time, modified, priority, url, status, timezone,
+    group_name
+) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
+ON CONFLICT(event_id) DO UPDATE SET
+    title = excluded.title,
+    description = excluded.description,
+    location = excluded.location,
+    categories = excluded.categories,
+    start_date = excluded.start_date,
+    start_time = excluded.start_time,
+    end_date = excluded.end_date,
+    end_time = excluded.end_time,
+    modified = excluded.modified,
+    priority = excluded.priority,
+    url = excluded.url,
+    status = excluded.status,
+    timezone = excluded.timezone,
+    group_name = excluded.group_name";
+
+/// Upsert events into the `calendar_events` table, creating it if needed
+///
+/// Rows are keyed by `event_id`, so inserting an event again updates its row
+/// rather than adding a second one. Dates are stored as `YYYY-MM-DD` and
+/// times as `HH:MM`, with the time columns `NULL` for all-day events.
+/// Categories are stored as a JSON array. Returns the number of events
+/// written.
+pub fn insert_events(conn: &Connection, events: &[CalendarEvent]) -> Result<usize> {
+    conn.execute(CREATE_TABLE, [])
+        .context("Failed to create calendar_events table")?;
+
+    let mut statement = conn
+        .prepare_cached(UPSERT)
+        .context("Failed to prepare event upsert")?;
+    let mut written = 0;
+    for event in events {
+        let (start_date, start_time) = columns(&event.start);
+        let (end_date, end_time) = columns(&event.end);
+        let categories =
+            serde_json::to_string(&event.categories).context("Failed to serialize categories")?;
+        written += statement
+            .execute(params![
+                event.event_id.as_str(),
+                event.title,
+                event.description,
+                event.location,
+                categories,
+                start_date,
+                start_time,
+                end_date,
+                end_time,
+                event
+                    .modified
+                    .map(|m| m.format("%Y-%m-%dT%H:%M:%S").to_string()),
+                event.priority.map(|p| format!("{:?}", p)),
+                event.url,
+                format!("{:?}", event.status),
+                event.timezone.map(|tz| tz.name()),
+                event.group,
+            ])
+            .context(format!("Failed to write event: {}", event.event_id))?;
+    }
+    Ok(written)
+}
+
+fn columns(time: &EventTime) -> (String, Option<String>) {
+    let date = time.date().format("%Y-%m-%d").to_string();
+    match time {
+        EventTime::AllDay(_) => (date, None),
+        EventTime::Specific { time, .. } => (date, Some(time.format("%H:%M").to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{all_day, date, event, timed};
+
+    #[test]
+    fn test_insert_events_upserts() {
+        let conn = Connection::open_in_memory().unwrap();
+        let day = date(2025, 12, 10);
+        let mut events = vec![
+            event("1", timed(day, 8, 30), timed(day, 9, 0)),
+            all_day("2", day),
+        ];
+        assert_eq!(insert_events(&conn, &events).unwrap(), 2);
+
+        events[0].title = "Chapel (moved)".to_string();
+        events[0].categories = vec!["Whole School".to_string()];
+        events[0].group = Some("Wellington".to_string());
+        assert_eq!(insert_events(&conn, &events).unwrap(), 2);
+
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM calendar_events", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 2);
+
+        let (title, categories, start_time, group_name): (
+            String,
+            String,
+            Option<String>,
+            Option<String>,
+        ) = conn
+            .query_row(
+                "SELECT title, categories, start_time, group_name \
+                 FROM calendar_events WHERE event_id = '1'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .unwrap();
+        assert_eq!(title, "Chapel (moved)");
+        assert_eq!(categories, r#"["Whole School"]"#);
+        assert_eq!(start_time.as_deref(), Some("08:30"));
+        assert_eq!(group_name.as_deref(), Some("Wellington"));
+
+        let all_day_time: Option<String> = conn
+            .query_row(
+                "SELECT start_time FROM calendar_events WHERE event_id = '2'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(all_day_time, None);
+    }
+}
@@ -0,0 +1,106 @@
+//! Builders for constructing events in unit tests
+
+use crate::models::{CalendarEvent, EventStatus, EventTime};
+use chrono::{NaiveDate, NaiveTime};
+
+pub fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+pub fn time(hour: u32, minute: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+}
+
+pub fn timed(date: NaiveDate, hour: u32, minute: u32) -> EventTime {
+    EventTime::Specific {
+        date,
+        time: time(hour, minute),
+    }
+}
+
+pub fn event(id: &str, start: EventTime, end: EventTime) -> CalendarEvent {
+    CalendarEvent {
+        event_id: id.into(),
+        title: format!("Event {}", id),
+        description: None,
+        location: String::new(),
+        categories: Vec::new(),
+        start,
+        end,
+        modified: None,
+        priority: None,
+        url: None,
+        status: EventStatus::Confirmed,
+        timezone: None,
+        group: None,
+        raw: None,
+    }
+}
+
+pub fn all_day(id: &str, date: NaiveDate) -> CalendarEvent {
+    event(id, EventTime::AllDay(date), EventTime::AllDay(date))
+}
+
+/// A local HTTP server that answers each connection with the next canned response
+#[cfg(feature = "client")]
+pub struct MockServer {
+    /// Base URL for the server, including a dummy ID/key query
+    pub url: String,
+    /// The raw requests received so far, in order
+    pub requests: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+}
+
+#[cfg(feature = "client")]
+pub fn mock_server(responses: Vec<String>) -> MockServer {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!(
+        "http://{}/socs/xml/SOCScalendar.ashx?ID=1&key=test",
+        listener.local_addr().unwrap()
+    );
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let received = Arc::clone(&requests);
+
+    std::thread::spawn(move || {
+        for response in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                match stream.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => request.extend_from_slice(&buf[..n]),
+                }
+            }
+            received
+                .lock()
+                .unwrap()
+                .push(String::from_utf8_lossy(&request).into_owned());
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+
+    MockServer { url, requests }
+}
+
+/// Build a 200 response carrying `body`
+#[cfg(feature = "client")]
+pub fn ok_response(body: &str) -> String {
+    format!(
+        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    )
+}
+
+/// Build an empty response with the given error status
+#[cfg(feature = "client")]
+pub fn error_response(status: u16) -> String {
+    format!(
+        "HTTP/1.1 {} Error\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        status
+    )
+}
@@ -0,0 +1,46 @@
+//! Exercises the parse path on its own so it can be run with
+//! `cargo test --no-default-features --test parse_only`.
+
+use chrono::NaiveDate;
+use socs_calendar_parser::{EventTime, parse_calendar_xml};
+
+const CALENDAR_XML: &str = r#"<SOCSCalendar>
+    <CalendarEvent>
+        <EventID>1001</EventID>
+        <StartDate>10/12/2025</StartDate>
+        <EndDate>10/12/2025</EndDate>
+        <StartTime>08:30</StartTime>
+        <EndTime>09:30</EndTime>
+        <Title>Chapel</Title>
+        <Description>Whole school service</Description>
+        <Location>Chapel</Location>
+        <Category>Whole School, Religious</Category>
+    </CalendarEvent>
+    <CalendarEvent>
+        <EventID>1002</EventID>
+        <StartDate>11/12/2025</StartDate>
+        <EndDate>11/12/2025</EndDate>
+        <StartTime>All Day</StartTime>
+        <Title>INSET Day</Title>
+        <Location></Location>
+        <Category>Staff</Category>
+    </CalendarEvent>
+</SOCSCalendar>"#;
+
+#[test]
+fn parses_without_client() {
+    let events = parse_calendar_xml(CALENDAR_XML.to_string()).unwrap();
+    assert_eq!(events.len(), 2);
+
+    let chapel = &events[0];
+    assert_eq!(chapel.event_id, "1001");
+    assert_eq!(chapel.categories, vec!["Whole School", "Religious"]);
+    assert!(!chapel.start.is_all_day());
+
+    let inset = &events[1];
+    assert_eq!(
+        inset.start,
+        EventTime::AllDay(NaiveDate::from_ymd_opt(2025, 12, 11).unwrap())
+    );
+    assert!(inset.description.is_none());
+}